@@ -4,14 +4,16 @@ import static org.junit.Assert.assertNotNull;
 import static org.junit.Assert.fail;
 
 import androidx.test.ext.junit.runners.AndroidJUnit4;
+import androidx.test.platform.app.InstrumentationRegistry;
+import com.ironcorelabs.sdk.IronOxide;
 import org.junit.Test;
 import org.junit.runner.RunWith;
 
 /**
  * Verifies that the rustls-platform-verifier classes are bundled in the
  * ironoxide-android AAR and available at runtime. If these classes are missing,
- * JNI_OnLoad will fail when loading the native library, causing a silent TLS
- * failure for consumers.
+ * IronOxide.initializePlatform will fail and consumers won't be able to make
+ * any requests.
  */
 @RunWith(AndroidJUnit4.class)
 public class RustlsBundledTest {
@@ -29,14 +31,23 @@ public class RustlsBundledTest {
 
     @Test
     public void nativeLibraryLoadsSuccessfully() {
-        // JNI_OnLoad calls init_rustls_platform_verifier, which looks up
-        // org/rustls/platformverifier/CertificateVerifier via JNI.
-        // If the class isn't on the classpath, this will throw UnsatisfiedLinkError.
         try {
             System.loadLibrary("ironoxide_android");
         } catch (UnsatisfiedLinkError e) {
-            fail("Failed to load native library. This may indicate the rustls "
-                    + "CertificateVerifier class is missing: " + e.getMessage());
+            fail("Failed to load native library: " + e.getMessage());
+        }
+    }
+
+    @Test
+    public void platformInitializesSuccessfully() {
+        // initializePlatform looks up org/rustls/platformverifier/CertificateVerifier
+        // through the app's class loader and throws if it can't be found.
+        System.loadLibrary("ironoxide_android");
+        try {
+            IronOxide.initializePlatform(
+                    InstrumentationRegistry.getInstrumentation().getTargetContext());
+        } catch (Exception e) {
+            fail("Failed to initialize the platform certificate verifier: " + e.getMessage());
         }
     }
 }
@@ -40,12 +40,13 @@ public class MainActivity extends AppCompatActivity implements MyRecyclerViewAda
     private final ArrayList<DocumentEncryptResult> encryptedList = new ArrayList<>();
     private MyRecyclerViewAdapter adapter;
 
-    // Load the ironoxide_android library, read the "device_context.json" raw resource,
+    // Load the ironoxide_android library, set up TLS for it, read the "device_context.json" raw resource,
     // and initialize an IronOxide to make calls
     private IronOxide initializeIronCore() {
         System.loadLibrary("ironoxide_android");
         String deviceString = rawResourceToString(R.raw.device_context);
         try {
+            IronOxide.initializePlatform(this);
             DeviceContext device = DeviceContext.fromJsonString(deviceString);
             return IronOxide.initialize(device, new IronOxideConfig());
         } catch (Exception e) {
@@ -1,6 +1,7 @@
 package com.ironcorelabs.sdk;
 
 import androidx.test.ext.junit.runners.AndroidJUnit4;
+import androidx.test.platform.app.InstrumentationRegistry;
 import org.junit.Test;
 import org.junit.runner.RunWith;
 import java.util.UUID;
@@ -12,6 +13,7 @@ public class FullIntegrationTest {
 
 	public FullIntegrationTest() throws Exception {
 		System.loadLibrary("ironoxide_android");
+		IronOxide.initializePlatform(InstrumentationRegistry.getInstrumentation().getTargetContext());
 		final java.io.InputStream in = FullIntegrationTest.class.getClassLoader()
 				.getResourceAsStream("deviceContext.json");
 		final java.util.Scanner s = new java.util.Scanner(in).useDelimiter("\\A");
@@ -19,6 +21,13 @@ public class FullIntegrationTest {
 		deviceContext = DeviceContext.fromJsonString(deviceString);
 	}
 
+	@Test
+	public void initializePlatformTwice() throws Exception {
+		IronOxide.initializePlatform(InstrumentationRegistry.getInstrumentation().getTargetContext());
+		final IronOxide io = IronOxide.initialize(deviceContext, new IronOxideConfig());
+		assertNotNull(io.documentList());
+	}
+
 	@Test
 	public void roundtripData() throws Exception {
 		final String data = "Test 123";
@@ -0,0 +1,11 @@
+package com.ironcorelabs.sdk;
+
+/**
+ * Thrown when IronOxide fails unexpectedly (a Rust panic) rather than because of a problem with
+ * the request. This is a bug in IronOxide or these bindings; the message describes what went wrong.
+ */
+public final class IronOxideInternalError extends RuntimeException {
+    public IronOxideInternalError(String message) {
+        super(message);
+    }
+}
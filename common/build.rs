@@ -1,6 +1,7 @@
 #![allow(unexpected_cfgs)]
 
 use std::{
+    collections::HashSet,
     env,
     path::{Path, PathBuf},
     time::Instant,
@@ -54,7 +55,7 @@ fn main() {
     let icl_expanded_lib_rs = format!("{}icl-expanded-lib.rs.in", out_dir);
     // Just before flapigen expands "lib.rs.in", we do our own expansion
     // This takes in "lib.rs.in" and outputs "icl-expanded-lib.rs.in", which is then fed to flapigen.
-    expand_equals_and_hashcode_macro(&icl_expanded_lib_rs);
+    expand_equals_and_hashcode_macro(&icl_expanded_lib_rs, out_path);
     flapigen_expand(Path::new(&icl_expanded_lib_rs), out_path);
     let expand_time = now.elapsed();
     println!(
@@ -63,6 +64,8 @@ fn main() {
     );
     println!("cargo:rerun-if-changed=../common/lib.rs.in");
     println!("cargo:rerun-if-changed=../common/lib.rs");
+    println!("cargo:rerun-if-changed=../common/IronOxideInternalError.java");
+    println!("cargo:rerun-if-changed=../cpp/IronOxideError.hpp");
 }
 
 #[cfg(feature = "java")]
@@ -122,31 +125,19 @@ fn flapigen_expand(from: &Path, out_dir: &Path) {
         .remove_not_generated_files_from_output_directory(true) //remove outdated *.java or cpp files
         .expand(name, from, out_dir.join("lib.rs"));
 
-    #[cfg(feature = "android")]
-    if let Ok("android") = std::env::var("CARGO_CFG_TARGET_OS").as_deref() {
-        // Post-process the generated lib.rs to inject rustls-platform-verifier
-        // initialization into JNI_OnLoad (see https://github.com/Dushistov/flapigen-rs/issues/440).
-        let generated = std::fs::read_to_string(out_dir.join("lib.rs"))
-            .expect("Failed to read generated lib.rs");
-        // The generated JNI_OnLoad ends with `SWIG_JNI_VERSION` followed by the
-        // closing brace of the function. Use a regex to handle any whitespace
-        // between them. We match only the return-value occurrence (followed by `}`)
-        // and not the GetEnv argument (followed by `,`).
-        let re = regex::Regex::new(r"SWIG_JNI_VERSION\s*\}")
-            .expect("Failed to compile JNI_OnLoad patch regex");
-        let patched = re.replacen(
-            &generated,
-            1,
-            "{ crate::init_rustls_platform_verifier(env); SWIG_JNI_VERSION } }",
-        );
-        assert_ne!(
-            *patched, generated,
-            "build.rs: Failed to patch JNI_OnLoad — the SWIG_JNI_VERSION pattern was not found \
-             in the generated lib.rs. The flapigen output format may have changed."
-        );
-        std::fs::write(out_dir.join("lib.rs"), patched.as_ref())
-            .expect("Failed to write patched lib.rs");
-    }
+    #[cfg(feature = "cpp")]
+    include_ostream(&get_cpp_codegen_output_directory());
+    #[cfg(feature = "cpp-exceptions")]
+    include_error_header(&get_cpp_codegen_output_directory());
+
+    #[cfg(not(feature = "cpp"))]
+    implement_serializable(&get_java_codegen_output_directory(out_dir));
+    #[cfg(not(feature = "cpp"))]
+    std::fs::write(
+        get_java_codegen_output_directory(out_dir).join("IronOxideInternalError.java"),
+        include_str!("IronOxideInternalError.java"),
+    )
+    .expect("Failed to write IronOxideInternalError.java");
 }
 
 cfg_if::cfg_if! {
@@ -175,9 +166,9 @@ cfg_if::cfg_if! {
     }
 }
 
-fn expand_equals_and_hashcode_macro(out: &str) {
+fn expand_equals_and_hashcode_macro(out: &str, out_dir: &Path) {
     cfg_if::cfg_if! {
-        if #[cfg(feature="cpp")] {
+        if #[cfg(feature="cpp-exceptions")] {
             let equals_and_hashcode = r##"
                 private fn eq(&self, o: &$1) -> bool; alias rustEq;
                 foreign_code r#"
@@ -185,6 +176,20 @@ fn expand_equals_and_hashcode_macro(out: &str) {
                     return lhs.rustEq(rhs);
                 }
                 
+                friend bool operator!=(const ${1}Wrapper &lhs, const ${1}Wrapper &rhs) {
+                    return !(lhs == rhs);
+                }
+            "#;"##;
+        } else if #[cfg(feature="cpp")] {
+            // without exceptions `rustEq` returns a variant, and an internal error counts as unequal
+            let equals_and_hashcode = r##"
+                private fn eq(&self, o: &$1) -> bool; alias rustEq;
+                foreign_code r#"
+                friend bool operator==(const ${1}Wrapper &lhs, const ${1}Wrapper &rhs) {
+                    auto equal = lhs.rustEq(rhs);
+                    return equal.index() == 0 && std::get<0>(equal);
+                }
+                
                 friend bool operator!=(const ${1}Wrapper &lhs, const ${1}Wrapper &rhs) {
                     return !(lhs == rhs);
                 }
@@ -208,5 +213,777 @@ fn expand_equals_and_hashcode_macro(out: &str) {
     let re = regex::Regex::new(r"pre_build_generate_equals_and_hashcode (.*);")
         .expect("unable to parse regex expression");
     let replaced = re.replace_all(&file, equals_and_hashcode).to_string();
+    let replaced = add_to_string(&replaced);
+    let replaced = expand_panic_for_test(&replaced);
+    #[cfg(feature = "cpp")]
+    let replaced = rename_overloads(&replaced);
+    let replaced = expand_platform_initialization(&replaced);
+    let replaced = expand_direct_byte_buffers(&replaced);
+    let replaced = expand_serializable(&replaced);
+    let replaced = expand_iterator_macro(&replaced);
+    let replaced = guard_declarations(&replaced, out_dir);
+    #[cfg(feature = "cpp-exceptions")]
+    let replaced = add_throwing_overloads(&replaced);
     std::fs::write(out, replaced).expect("unable to output file");
 }
+
+/// Give every class a `toString()` (and in C++ an `operator<<`) that shows `Describe::describe`
+/// from lib.rs, which keeps secrets out of it.
+fn add_to_string(file: &str) -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(feature="cpp-exceptions")] {
+            let to_string = r##"${1}self_type $2;
+    fn describe(&self) -> String; alias toString;
+    foreign_code r#"
+    friend std::ostream &operator<<(std::ostream &os, const ${2}Wrapper &obj) {
+        return os << obj.toString().to_std_string();
+    }
+    "#;"##;
+        } else if #[cfg(feature="cpp")] {
+            // without exceptions `toString` returns a variant, and an internal error is written instead
+            let to_string = r##"${1}self_type $2;
+    fn describe(&self) -> String; alias toString;
+    foreign_code r#"
+    friend std::ostream &operator<<(std::ostream &os, const ${2}Wrapper &obj) {
+        auto described = obj.toString();
+        return os << (described.index() == 0 ? std::get<0>(described).to_std_string()
+                                              : std::get<1>(described).message());
+    }
+    "#;"##;
+        } else {
+            let to_string = r##"${1}self_type $2;
+    fn describe(&self) -> String; alias toString;"##;
+        }
+    }
+    let re = regex::Regex::new(r"(?m)^([ \t]*)self_type (\w+);")
+        .expect("unable to parse regex expression");
+    re.replace_all(file, to_string).to_string()
+}
+
+/// `IronOxide.panicForTest` always panics, so the tests can check that a panic reaches the caller as an
+/// error. It's only declared with the `panic-for-test` feature, which the tests are built with, so it's
+/// never part of a release.
+fn expand_panic_for_test(file: &str) -> String {
+    let panic_for_test = if cfg!(feature = "panic-for-test") {
+        r"/// Always fails with an internal error. Only built with the `panic-for-test` feature, for the tests.
+    fn ffi_guard::panic_for_test(message: &str) -> Result<usize, String>; alias panicForTest;"
+    } else {
+        ""
+    };
+    file.replace("pre_build_panic_for_test;", panic_for_test)
+}
+
+/// flapigen names the generated C functions after the method alias, so C++ can't have two methods
+/// with the same alias. Give the per-call timeout and retry policy overloads a `WithTimeout` or
+/// `WithRetry` suffix instead.
+#[cfg(feature = "cpp")]
+fn rename_overloads(file: &str) -> String {
+    let re = regex::Regex::new(r"(fn \w+_with_(timeout|retry)\([^;]*;\s*alias )(\w+);")
+        .expect("unable to parse regex expression");
+    re.replace_all(file, |caps: &regex::Captures| {
+        let suffix = if &caps[2] == "timeout" {
+            "WithTimeout"
+        } else {
+            "WithRetry"
+        };
+        format!("{}{}{};", &caps[1], &caps[3], suffix)
+    })
+    .to_string()
+}
+
+/// Panics mustn't unwind out of the generated `extern "C"` functions, so every function and constructor
+/// declared in lib.rs.in is pointed at a wrapper in `ffi_guarded` that catches a panic and returns it as an
+/// error. The wrappers are written to `ffi_guarded.rs`, which lib.rs includes.
+///
+/// Declarations returning `Result<T, String>` or `Result<T, Error>` return `Result<T, FfiError>`. On the JVM
+/// the others return `Guarded<T>`, which throws without adding `throws Exception` to the Java method, and
+/// constructors return `Result<T, FfiError>`. C++ can only report an error by returning it, so there every function returns a
+/// `Result`, and since a generated constructor can't fail, constructors become static factories named after
+/// them, such as `create` and `createDefault`.
+fn guard_declarations(file: &str, out_dir: &Path) -> String {
+    let self_types: Vec<(usize, &str)> = regex::Regex::new(r"self_type (\w+);")
+        .expect("unable to parse regex expression")
+        .captures_iter(file)
+        .map(|caps| (caps.get(0).unwrap().start(), caps.get(1).unwrap().as_str()))
+        .collect();
+    let re = regex::Regex::new(
+        r"(?m)^([ \t]*)(private )?(fn|constructor) ([\w:]+)\(([^)]*)\)\s*(?:->\s*([^;{]+?))?\s*;(?:\s*alias (\w+);)?",
+    )
+    .expect("unable to parse regex expression");
+    let mut wrappers = String::new();
+    let mut names = HashSet::new();
+    let mut classes_with_factories = HashSet::new();
+    let guarded = re.replace_all(file, |caps: &regex::Captures| {
+        let start = caps.get(0).unwrap().start();
+        let (indent, kind, path, args) = (&caps[1], &caps[3], &caps[4], &caps[5]);
+        let private = caps.get(2).map_or("", |m| m.as_str());
+        let ret = caps.get(6).map_or("()", |m| m.as_str());
+        let self_type = self_types
+            .iter()
+            .rev()
+            .find(|(position, _)| *position < start)
+            .map(|(_, self_type)| *self_type)
+            .unwrap_or_else(|| panic!("`{path}` isn't declared in a class with a self_type"));
+        let fn_name = path.rsplit("::").next().unwrap_or(path);
+        let base = format!("{}_{fn_name}", snake_case(self_type));
+        let mut name = base.clone();
+        for n in 2.. {
+            if names.insert(name.clone()) {
+                break;
+            }
+            name = format!("{base}_{n}");
+        }
+
+        let (params, call_args): (Vec<_>, Vec<_>) = split_args(args)
+            .into_iter()
+            .map(|arg| match arg {
+                "&self" => (format!("this: &{self_type}"), "this"),
+                "&mut self" => (format!("this: &mut {self_type}"), "this"),
+                _ => {
+                    let (arg_name, _) = arg
+                        .split_once(':')
+                        .unwrap_or_else(|| panic!("unexpected argument `{arg}` of `{path}`"));
+                    (arg.to_string(), arg_name.trim())
+                }
+            })
+            .unzip();
+        let call = format!("super::{path}({})", call_args.join(", "));
+        let ok_type = ret
+            .strip_prefix("Result<")
+            .and_then(|ret| ret.strip_suffix('>'))
+            .and_then(|ret| ret.rsplit_once(','))
+            .filter(|(_, err)| matches!(err.trim(), "String" | "Error"))
+            .map(|(ok, _)| ok.trim());
+        let (guarded_ret, body) = match ok_type {
+            Some(ok) => (
+                format!("Result<{ok}, FfiError>"),
+                format!("crate::ffi_guard::result(|| {call})"),
+            ),
+            None if cfg!(feature = "cpp") || kind == "constructor" => (
+                format!("Result<{ret}, FfiError>"),
+                format!("crate::ffi_guard::infallible(|| {call})"),
+            ),
+            None => (
+                format!("Guarded<{ret}>"),
+                format!("crate::ffi_guard::guarded(|| {call})"),
+            ),
+        };
+        wrappers.push_str(&format!(
+            "pub fn {name}({}) -> {guarded_ret} {{\n    {body}\n}}\n\n",
+            params.join(", ")
+        ));
+
+        let alias = caps.get(7).map_or(fn_name, |m| m.as_str());
+        match kind {
+            "fn" => format!(
+                "{indent}{private}fn ffi_guarded::{name}({args}) -> {guarded_ret}; alias {alias};"
+            ),
+            _ if cfg!(feature = "cpp") => {
+                // the class still needs a constructor; it goes after the factory to keep the docs on it
+                let constructor = if classes_with_factories.insert(self_type) {
+                    format!("\n{indent}private constructor = empty;")
+                } else {
+                    String::new()
+                };
+                format!(
+                    "{indent}fn ffi_guarded::{name}({args}) -> {guarded_ret}; alias {};{constructor}",
+                    factory_name(fn_name)
+                )
+            }
+            _ => format!("{indent}{private}constructor ffi_guarded::{name}({args}) -> {guarded_ret};"),
+        }
+    });
+    std::fs::write(out_dir.join("ffi_guarded.rs"), wrappers)
+        .expect("unable to output the guarded functions");
+    guarded.to_string()
+}
+
+/// Split a declaration's arguments on the commas between them.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut split = vec![];
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                split.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    split.push(&args[start..]);
+    split
+        .into_iter()
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .collect()
+}
+
+/// The C++ factory standing in for a constructor: `create` for `new` or `create`, otherwise `create`
+/// followed by the rest of the name in camel case, such as `createDefault` or `createFromDar`.
+fn factory_name(constructor: &str) -> String {
+    let rest = constructor
+        .strip_prefix("new")
+        .or_else(|| constructor.strip_prefix("create"))
+        .unwrap_or(constructor);
+    rest.split('_')
+        .filter(|word| !word.is_empty())
+        .fold("create".to_string(), |name, word| {
+            let mut chars = word.chars();
+            let first: String = chars
+                .next()
+                .map_or(String::new(), |c| c.to_uppercase().collect());
+            name + &first + chars.as_str()
+        })
+}
+
+fn snake_case(class: &str) -> String {
+    regex::Regex::new(r"([a-z])([A-Z])")
+        .expect("unable to parse regex expression")
+        .replace_all(class, "${1}_${2}")
+        .to_lowercase()
+}
+
+/// flapigen declares the C++ methods it generates `noexcept`, so they can only return an error, and C++
+/// exceptions are layered on top of them. Every method returning `Result<_, FfiError>` is made private with an
+/// `OrError` suffix, and a method with the original name, docs and C++ types is added that forwards to it and
+/// throws an `IronOxideError` instead of returning the error.
+#[cfg(feature = "cpp-exceptions")]
+fn add_throwing_overloads(file: &str) -> String {
+    let names = |pattern: &str| -> HashSet<String> {
+        regex::Regex::new(pattern)
+            .expect("unable to parse regex expression")
+            .captures_iter(file)
+            .map(|caps| caps[1].to_string())
+            .collect()
+    };
+    let types = CppTypes {
+        classes: names(r"(?m)(?:^|\()[ \t]*class (\w+)"),
+        enums: names(r"(?m)^[ \t]*enum (\w+)"),
+    };
+    let re = regex::Regex::new(
+        r"(?m)((?:^[ \t]*///.*\n)*)^([ \t]*)(private )?fn ([\w:]+)\(([^;]*?)\)\s*->\s*Result<([^;]*),\s*FfiError>;(?:\s*alias (\w+);)?",
+    )
+    .expect("unable to parse regex expression");
+    re.replace_all(file, |caps: &regex::Captures| {
+        let (docs, indent, path, args, ok) = (&caps[1], &caps[2], &caps[4], &caps[5], &caps[6]);
+        let name = caps.get(7).map_or_else(
+            || path.rsplit("::").next().unwrap_or(path),
+            |alias| alias.as_str(),
+        );
+        let docs: String = docs
+            .lines()
+            .map(|line| format!("    {}\n", line.trim()))
+            .collect();
+        let (mut qualifier, mut constness) = ("static ", "");
+        let mut params = vec![];
+        let mut arg_names = vec![];
+        for arg in split_args(args) {
+            match arg {
+                "&self" => (qualifier, constness) = ("", " const"),
+                "&mut self" => qualifier = "",
+                _ => {
+                    let (arg_name, ty) = arg
+                        .split_once(':')
+                        .unwrap_or_else(|| panic!("unexpected argument `{arg}` of `{path}`"));
+                    let cpp_type = types
+                        .cpp(ty)
+                        .unwrap_or_else(|| panic!("no C++ type is known for `{ty}` in `{path}`"));
+                    let separator = if cpp_type.ends_with(['&', '*']) {
+                        ""
+                    } else {
+                        " "
+                    };
+                    params.push(format!("{cpp_type}{separator}{}", arg_name.trim()));
+                    arg_names.push(arg_name.trim());
+                }
+            }
+        }
+        // `auto` for the few results flapigen names in a way that isn't worked out here
+        let ret = types.cpp(ok).unwrap_or_else(|| "auto".to_string());
+        // the forwarder has the access the declaration had; later foreign code expects to be public
+        let (access, restore) = if caps.get(3).is_some() {
+            ("private:", "public:\n")
+        } else {
+            ("public:", "")
+        };
+        format!(
+            r##"{indent}private fn {path}({args}) -> Result<{ok}, FfiError>; alias {name}OrError;
+{indent}foreign_code r#"
+{access}
+{docs}    {qualifier}{ret} {name}({params}){constness} {{
+        return throw_on_error({name}OrError({arg_names}));
+    }}
+{restore}"#;"##,
+            params = params.join(", "),
+            arg_names = arg_names.join(", "),
+        )
+    })
+    .to_string()
+}
+
+/// The classes and enums declared in lib.rs.in, which flapigen maps to C++ types of the same name.
+#[cfg(feature = "cpp-exceptions")]
+struct CppTypes {
+    classes: HashSet<String>,
+    enums: HashSet<String>,
+}
+
+#[cfg(feature = "cpp-exceptions")]
+impl CppTypes {
+    /// The C++ type flapigen uses for an argument or result of Rust type `ty`, following its own typemaps
+    /// and those in cpp_typemaps.rs. `None` if it isn't one of the types lib.rs.in uses.
+    fn cpp(&self, ty: &str) -> Option<String> {
+        let ty = ty.trim();
+        let class = |name: &str| self.classes.contains(name).then(|| name.to_string());
+        let primitive = match ty {
+            "()" => Some("void"),
+            "bool" => Some("bool"),
+            "u8" => Some("uint8_t"),
+            "i32" => Some("int32_t"),
+            "u32" => Some("uint32_t"),
+            "i64" | "OffsetDateTime" => Some("int64_t"),
+            "u64" => Some("uint64_t"),
+            "usize" => Some("uintptr_t"),
+            "String" => Some("RustString"),
+            "&str" => Some("std::string_view"),
+            "&[i8]" => Some("ByteView"),
+            "Vec<i8>" => Some("std::vector<uint8_t>"),
+            "Vec<i32>" => Some("RustVeci32"),
+            _ => None,
+        };
+        if let Some(primitive) = primitive {
+            return Some(primitive.to_string());
+        }
+        let inner = |prefix: &str, suffix: &str| {
+            ty.strip_prefix(prefix)
+                .and_then(|rest| rest.strip_suffix(suffix))
+        };
+        if let Some(t) = inner("Option<&", ">").and_then(class) {
+            Some(format!("const {t} *"))
+        } else if let Some(t) = inner("Option<", ">") {
+            self.cpp(t).map(|t| format!("std::optional<{t}>"))
+        } else if let Some(t) = inner("&[", "]") {
+            class(t).map(|t| format!("RustForeignSliceConst<{t}Ref>"))
+        } else if let Some(t) = inner("Vec<", ">") {
+            class(t).map(|t| format!("RustForeignVec{t}"))
+        } else if let Some(t) = ty.strip_prefix("&mut ") {
+            class(t).map(|t| format!("{t} &"))
+        } else if let Some(t) = ty.strip_prefix('&') {
+            class(t).map(|t| format!("const {t} &"))
+        } else {
+            class(ty).or_else(|| self.enums.contains(ty).then(|| ty.to_string()))
+        }
+    }
+}
+
+/// The `operator<<` added by `add_to_string` needs `std::ostream`, which the generated headers
+/// don't otherwise include.
+#[cfg(feature = "cpp")]
+fn include_ostream(dir: &Path) {
+    for entry in std::fs::read_dir(dir).expect("Failed to read the generated headers") {
+        let path = entry.expect("Failed to read the generated headers").path();
+        if path.extension().is_none_or(|ext| ext != "hpp") {
+            continue;
+        }
+        let header = std::fs::read_to_string(&path).expect("Failed to read a generated header");
+        if header.contains("operator<<(") {
+            let header =
+                header.replacen("#pragma once\n", "#pragma once\n\n#include <ostream>\n", 1);
+            std::fs::write(&path, header).expect("Failed to write a generated header");
+        }
+    }
+}
+
+/// Make the classes marked `pre_build_generate_serializable` `Serializable`, and on Android `Parcelable`
+/// as well. Both write the class's binary form from `binary` in `lib.rs`, since the Java object only
+/// holds a pointer to the Rust one. Serialization goes through a `SerializedForm` proxy so the pointer
+/// itself is never written. C++ has neither, so the markers are dropped there.
+fn expand_serializable(file: &str) -> String {
+    let re = regex::Regex::new(r"pre_build_generate_serializable (\w+);")
+        .expect("unable to parse regex expression");
+    re.replace_all(file, |caps: &regex::Captures| serializable(&caps[1]))
+        .to_string()
+}
+
+#[cfg(feature = "cpp")]
+fn serializable(_class: &str) -> String {
+    String::new()
+}
+
+#[cfg(not(feature = "cpp"))]
+fn serializable(class: &str) -> String {
+    let snake_case = snake_case(class);
+    let serializable = format!(
+        r##"private fn encode_binary(&self) -> Result<Vec<i8>, Error>; alias encodeBinary;
+    private fn binary::decode_{snake_case}(bytes: &[i8]) -> Result<{class}, String>; alias decodeBinary;
+    foreign_code r#"
+    private Object writeReplace() throws java.io.ObjectStreamException {{
+        try {{
+            return new SerializedForm(encodeBinary());
+        }} catch (Exception e) {{
+            java.io.NotSerializableException notSerializable = new java.io.NotSerializableException(e.getMessage());
+            notSerializable.initCause(e);
+            throw notSerializable;
+        }}
+    }}
+
+    private void readObject(java.io.ObjectInputStream in) throws java.io.InvalidObjectException {{
+        throw new java.io.InvalidObjectException("{class} is only read through its SerializedForm");
+    }}
+
+    private static final class SerializedForm implements java.io.Serializable {{
+        private static final long serialVersionUID = 1L;
+        private final byte[] bytes;
+
+        SerializedForm(byte[] bytes) {{
+            this.bytes = bytes;
+        }}
+
+        private Object readResolve() throws java.io.ObjectStreamException {{
+            try {{
+                return {class}.decodeBinary(bytes);
+            }} catch (Exception e) {{
+                java.io.InvalidObjectException invalid = new java.io.InvalidObjectException(e.getMessage());
+                invalid.initCause(e);
+                throw invalid;
+            }}
+        }}
+    }}
+"#;"##
+    );
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "android")] {
+            let parcelable = format!(
+                r##"
+    foreign_code r#"
+    @Override
+    public int describeContents() {{
+        return 0;
+    }}
+
+    @Override
+    public void writeToParcel(android.os.Parcel dest, int flags) {{
+        try {{
+            dest.writeByteArray(encodeBinary());
+        }} catch (Exception e) {{
+            throw new IllegalStateException(e.getMessage(), e);
+        }}
+    }}
+
+    public static final android.os.Parcelable.Creator<{class}> CREATOR = new android.os.Parcelable.Creator<{class}>() {{
+        @Override
+        public {class} createFromParcel(android.os.Parcel in) {{
+            try {{
+                return {class}.decodeBinary(in.createByteArray());
+            }} catch (Exception e) {{
+                throw new IllegalArgumentException(e.getMessage(), e);
+            }}
+        }}
+
+        @Override
+        public {class}[] newArray(int size) {{
+            return new {class}[size];
+        }}
+    }};
+"#;"##
+            );
+        } else {
+            let parcelable = String::new();
+        }
+    }
+    serializable + &parcelable
+}
+
+/// flapigen can't declare the interfaces a class implements, so they're added to the generated classes
+/// that have the methods from `expand_serializable`.
+#[cfg(not(feature = "cpp"))]
+fn implement_serializable(dir: &Path) {
+    let re = regex::Regex::new(r"(?m)^(public final class \w+) \{")
+        .expect("Failed to compile class declaration regex");
+    let interfaces = if cfg!(feature = "android") {
+        "java.io.Serializable, android.os.Parcelable"
+    } else {
+        "java.io.Serializable"
+    };
+    for entry in std::fs::read_dir(dir).expect("Failed to read the generated classes") {
+        let path = entry.expect("Failed to read the generated classes").path();
+        if path.extension().is_none_or(|ext| ext != "java") {
+            continue;
+        }
+        let class = std::fs::read_to_string(&path).expect("Failed to read a generated class");
+        if class.contains("private Object writeReplace()") {
+            assert!(
+                re.is_match(&class),
+                "build.rs: no class declaration was found in {path:?}. \
+                 The flapigen output format may have changed."
+            );
+            let implemented = re.replacen(&class, 1, format!("${{1}} implements {interfaces} {{"));
+            std::fs::write(&path, implemented.as_bytes())
+                .expect("Failed to write a generated class");
+        }
+    }
+}
+
+/// The methods added by `add_throwing_overloads` need `IronOxideError.hpp`, which flapigen doesn't know
+/// about, so it's written next to the generated headers and included from those that use it.
+#[cfg(feature = "cpp-exceptions")]
+fn include_error_header(dir: &Path) {
+    std::fs::write(
+        dir.join("IronOxideError.hpp"),
+        include_str!("../cpp/IronOxideError.hpp"),
+    )
+    .expect("Failed to write IronOxideError.hpp");
+    for entry in std::fs::read_dir(dir).expect("Failed to read the generated headers") {
+        let path = entry.expect("Failed to read the generated headers").path();
+        if path.extension().is_none_or(|ext| ext != "hpp") {
+            continue;
+        }
+        let header = std::fs::read_to_string(&path).expect("Failed to read a generated header");
+        if header.contains("throw_on_error(") {
+            let header = header.replacen(
+                "#pragma once\n",
+                "#pragma once\n\n#include \"IronOxideError.hpp\"\n",
+                1,
+            );
+            std::fs::write(&path, header).expect("Failed to write a generated header");
+        }
+    }
+}
+
+/// Only Android needs `IronOxide.initializePlatform(Context)`, and `Context` doesn't exist anywhere else,
+/// so the Java for it is only generated for Android. The native half is in `lib.rs`.
+fn expand_platform_initialization(file: &str) -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "android")] {
+            let platform_initialization = r##"foreign_code r#"
+    /**
+     * Set up TLS certificate verification using the Android platform trust store. Call this once,
+     * for example from {@code Application.onCreate}, before using any IronOxide method that contacts
+     * the IronCore webservice. Until then those methods fail without making a request. Calling it
+     * again has no effect.
+     *
+     * @param context  any Context belonging to the app; its application context is used when available
+     * @throws Exception if certificate verification couldn't be set up
+     */
+    public static void initializePlatform(android.content.Context context) throws Exception {
+        if (context == null) {
+            throw new IllegalArgumentException("context must not be null");
+        }
+        android.content.Context appContext = context.getApplicationContext();
+        do_initializePlatform(appContext != null ? appContext : context);
+    }
+    private static native void do_initializePlatform(android.content.Context context) throws Exception;
+"#;"##;
+        } else {
+            let platform_initialization = "";
+        }
+    }
+    file.replace(
+        "pre_build_platform_initialization;",
+        platform_initialization,
+    )
+}
+
+/// The `ByteBuffer` overloads of the document encrypt and decrypt methods only make sense for the JVM, and
+/// `DirectByteBuffer` only has a JNI typemap, so they're left out of the C++ bindings. The native half is
+/// `direct_buffer` in `lib.rs`.
+fn expand_direct_byte_buffers(file: &str) -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "cpp")] {
+            let direct_byte_buffers = "";
+        } else {
+            let direct_byte_buffers = r##"
+    private fn direct_buffer::document_encrypt(&self, data: DirectByteBuffer, out: DirectByteBuffer, encryptOpts: &DocumentEncryptOpts)
+        -> Result<DocumentEncryptDirectResult, String>; alias do_documentEncryptDirect;
+    private fn direct_buffer::document_decrypt(&self, encryptedData: DirectByteBuffer, out: DirectByteBuffer)
+        -> Result<DocumentDecryptDirectResult, String>; alias do_documentDecryptDirect;
+    private fn direct_buffer::document_encrypt_unmanaged(&self, data: DirectByteBuffer, out: DirectByteBuffer, encryptOpts: &DocumentEncryptOpts)
+        -> Result<DocumentEncryptDirectResult, String>; alias do_documentEncryptUnmanagedDirect;
+    private fn direct_buffer::document_decrypt_unmanaged(&self, encryptedData: DirectByteBuffer, encryptedDeks: &[i8], out: DirectByteBuffer)
+        -> Result<DocumentDecryptDirectResult, String>; alias do_documentDecryptUnmanagedDirect;
+    private fn direct_buffer::document_decrypted_size(encryptedData: DirectByteBuffer) -> Result<usize, String>; alias do_documentDecryptedSize;
+    /// Number of bytes the output buffer needs for encrypting a document of the given size with
+    /// {@link #documentEncrypt(java.nio.ByteBuffer, java.nio.ByteBuffer, DocumentEncryptOpts)} or
+    /// {@link #documentEncryptUnmanaged(java.nio.ByteBuffer, java.nio.ByteBuffer, DocumentEncryptOpts)}. This is
+    /// enough for any document ID, so the encrypted document may be a little shorter.
+    fn direct_buffer::document_encrypted_size(&self, plaintextLength: usize) -> usize; alias documentEncryptedSize;
+    foreign_code r#"
+    private static void requireDirect(java.nio.ByteBuffer... buffers) {
+        for (java.nio.ByteBuffer buffer : buffers) {
+            if (!buffer.isDirect()) {
+                throw new IllegalArgumentException("ByteBuffer must be direct. Use ByteBuffer.allocateDirect.");
+            }
+        }
+    }
+
+    private static void requireWritable(java.nio.ByteBuffer out) {
+        // native code writes through the buffer's address, which would get around it being read-only
+        if (out.isReadOnly()) {
+            throw new java.nio.ReadOnlyBufferException();
+        }
+    }
+
+    /**
+     * Number of bytes the output buffer needs for decrypting the remaining bytes of {@code encryptedData}.
+     * Only the document header is read, and the buffer's position isn't changed.
+     *
+     * @param encryptedData  direct buffer holding an encrypted document
+     * @throws Exception if the data isn't an encrypted document
+     */
+    public static long documentDecryptedSize(java.nio.ByteBuffer encryptedData) throws Exception {
+        requireDirect(encryptedData);
+        return do_documentDecryptedSize(encryptedData.slice());
+    }
+
+    /**
+     * Encrypt the remaining bytes of {@code data} into {@code out}, without copying either onto the Java heap.
+     * Otherwise the same as {@link #documentEncrypt(byte[], DocumentEncryptOpts)}. Both buffers must be direct.
+     * On success {@code data} is fully read and {@code out}'s position is moved past the encrypted bytes.
+     *
+     * @param data         direct buffer holding the bytes to encrypt
+     * @param out          writable direct buffer with at least {@link #documentEncryptedSize} bytes remaining
+     * @param encryptOpts  optional document encrypt parameters
+     */
+    public DocumentEncryptDirectResult documentEncrypt(java.nio.ByteBuffer data, java.nio.ByteBuffer out, DocumentEncryptOpts encryptOpts) throws Exception {
+        requireDirect(data, out);
+        requireWritable(out);
+        DocumentEncryptDirectResult result = do_documentEncryptDirect(data.slice(), out.slice(), encryptOpts);
+        data.position(data.limit());
+        out.position(out.position() + (int) result.getLength());
+        return result;
+    }
+
+    /**
+     * Decrypt the remaining bytes of {@code encryptedData} into {@code out}, without copying either onto the Java
+     * heap. Otherwise the same as {@link #documentDecrypt(byte[])}. Both buffers must be direct. On success
+     * {@code encryptedData} is fully read and {@code out}'s position is moved past the decrypted bytes.
+     *
+     * @param encryptedData  direct buffer holding the encrypted document
+     * @param out            writable direct buffer with at least {@link #documentDecryptedSize} bytes remaining
+     */
+    public DocumentDecryptDirectResult documentDecrypt(java.nio.ByteBuffer encryptedData, java.nio.ByteBuffer out) throws Exception {
+        requireDirect(encryptedData, out);
+        requireWritable(out);
+        DocumentDecryptDirectResult result = do_documentDecryptDirect(encryptedData.slice(), out.slice());
+        encryptedData.position(encryptedData.limit());
+        out.position(out.position() + (int) result.getLength());
+        return result;
+    }
+
+    /**
+     * Encrypt the remaining bytes of {@code data} into {@code out} as an unmanaged document, without copying either
+     * onto the Java heap. Otherwise the same as {@link #documentEncryptUnmanaged(byte[], DocumentEncryptOpts)}, with
+     * the EDEKs in {@link DocumentEncryptDirectResult#getEncryptedDeks}. Both buffers must be direct. On success
+     * {@code data} is fully read and {@code out}'s position is moved past the encrypted bytes.
+     *
+     * @param data         direct buffer holding the bytes to encrypt
+     * @param out          writable direct buffer with at least {@link #documentEncryptedSize} bytes remaining
+     * @param encryptOpts  document encrypt parameters, as for {@link #documentEncryptUnmanaged(byte[], DocumentEncryptOpts)}
+     */
+    public DocumentEncryptDirectResult documentEncryptUnmanaged(java.nio.ByteBuffer data, java.nio.ByteBuffer out, DocumentEncryptOpts encryptOpts) throws Exception {
+        requireDirect(data, out);
+        requireWritable(out);
+        DocumentEncryptDirectResult result = do_documentEncryptUnmanagedDirect(data.slice(), out.slice(), encryptOpts);
+        data.position(data.limit());
+        out.position(out.position() + (int) result.getLength());
+        return result;
+    }
+
+    /**
+     * Decrypt the remaining bytes of {@code encryptedData} into {@code out}, without copying either onto the Java
+     * heap. Otherwise the same as {@link #documentDecryptUnmanaged(byte[], byte[])}. Both buffers must be direct. On
+     * success {@code encryptedData} is fully read and {@code out}'s position is moved past the decrypted bytes.
+     *
+     * @param encryptedData  direct buffer holding the encrypted document
+     * @param encryptedDeks  encrypted document encryption keys returned when the document was encrypted
+     * @param out            writable direct buffer with at least {@link #documentDecryptedSize} bytes remaining
+     */
+    public DocumentDecryptDirectResult documentDecryptUnmanaged(java.nio.ByteBuffer encryptedData, byte[] encryptedDeks, java.nio.ByteBuffer out) throws Exception {
+        requireDirect(encryptedData, out);
+        requireWritable(out);
+        DocumentDecryptDirectResult result = do_documentDecryptUnmanagedDirect(encryptedData.slice(), encryptedDeks, out.slice());
+        encryptedData.position(encryptedData.limit());
+        out.position(out.position() + (int) result.getLength());
+        return result;
+    }
+"#;"##;
+        }
+    }
+    file.replace("pre_build_direct_byte_buffers;", direct_byte_buffers)
+}
+
+/// `pre_build_generate_iterator Cursor Item;` lets a class with `fn next(&mut self) -> Option<Item>` be
+/// used with the language's own iteration: it gets `iterator()` in Java and `begin()`/`end()` in C++.
+fn expand_iterator_macro(file: &str) -> String {
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "cpp")] {
+            let iterator = r##"foreign_code r#"
+                class iterator {
+                public:
+                    using iterator_category = std::input_iterator_tag;
+                    using value_type = $2;
+                    using difference_type = std::ptrdiff_t;
+                    using pointer = $2 *;
+                    using reference = $2 &;
+
+                    iterator() = default;
+                    explicit iterator(${1}Wrapper *cursor) : cursor_(cursor) { ++*this; }
+
+                    reference operator*() { return *current_; }
+                    pointer operator->() { return &*current_; }
+                    iterator &operator++() {
+                        advance_current;
+                        if (!current_) {
+                            cursor_ = nullptr;
+                        }
+                        return *this;
+                    }
+                    friend bool operator==(const iterator &lhs, const iterator &rhs) { return lhs.cursor_ == rhs.cursor_; }
+                    friend bool operator!=(const iterator &lhs, const iterator &rhs) { return !(lhs == rhs); }
+
+                private:
+                    ${1}Wrapper *cursor_ = nullptr;
+                    std::optional<$2> current_;
+                };
+
+                iterator begin() { return iterator(this); }
+                iterator end() { return iterator(); }
+            "#;"##;
+            #[cfg(feature = "cpp-exceptions")]
+            let advance_current = "current_ = cursor_->next()";
+            // without exceptions `next` returns a variant, and an internal error ends the iteration
+            #[cfg(not(feature = "cpp-exceptions"))]
+            let advance_current = "auto next = cursor_->next();
+                        current_ = next.index() == 0 ? std::get<0>(std::move(next)) : std::nullopt";
+            let iterator = iterator.replace("advance_current", advance_current);
+        } else {
+            let iterator = r##"foreign_code r#"
+    /**
+     * Iterator over the remaining items. Advancing it advances this object as well.
+     */
+    public java.util.Iterator<$2> iterator() {
+        return new java.util.Iterator<$2>() {
+            private java.util.Optional<$2> upcoming = $1.this.next();
+
+            public boolean hasNext() {
+                return upcoming.isPresent();
+            }
+
+            public $2 next() {
+                $2 item = upcoming.orElseThrow(java.util.NoSuchElementException::new);
+                upcoming = $1.this.next();
+                return item;
+            }
+        };
+    }
+"#;"##;
+        }
+    }
+    let re = regex::Regex::new(r"pre_build_generate_iterator (\w+) (\w+);")
+        .expect("unable to parse regex expression");
+    re.replace_all(file, &*iterator).to_string()
+}
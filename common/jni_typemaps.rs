@@ -39,4 +39,46 @@ foreign_typemap!(
     };
     ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*slice*/") <= "/*slice*/swig_f_type!(T) []";
     ($p:f_type, option = "NullAnnotations", unique_prefix = "/*slice*/") <= "/*slice*/@NonNull swig_f_type!(T, NoNullAnnotations) []";
-);
\ No newline at end of file
+);
+// lets a direct `java.nio.ByteBuffer` be passed to native code without copying it.
+// see `direct_buffer::DirectByteBuffer` in lib.rs
+foreign_typemap!(
+    ($p:r_type) DirectByteBuffer <= jobject {
+        $out = DirectByteBuffer::new(env, $p);
+    };
+    ($p:f_type, option = "NoNullAnnotations", unique_prefix = "/*direct*/") <= "/*direct*/java.nio.ByteBuffer";
+    ($p:f_type, option = "NullAnnotations", unique_prefix = "/*direct*/") <= "/*direct*/@NonNull java.nio.ByteBuffer";
+);
+// everything declared in lib.rs.in is called through `ffi_guarded`, which returns one of these; see `ffi_guard` in lib.rs.
+// a `Result` declares `throws Exception`, like flapigen's `Result<T, String>`, and a panic is thrown as an `IronOxideInternalError`.
+foreign_typemap!(
+    ($p:r_type) <T> Result<T, FfiError> => swig_i_type!(T) {
+        $out = match $p {
+            Ok(x) => {
+                swig_from_rust_to_i_type!(T, x, ret)
+                ret
+            }
+            Err(err) => {
+                err.throw(env);
+                return <swig_i_type!(T)>::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix = "/*Result<swig_subst_type!(T), FfiError>*/") => "/*Result<swig_subst_type!(T), FfiError>*/swig_f_type!(T)" "swig_foreign_from_i_type!(T, $p)";
+);
+// calls that can't fail otherwise only throw the unchecked `IronOxideInternalError`
+foreign_typemap!(
+    ($p:r_type) <T> Guarded<T> => swig_i_type!(T) {
+        $out = match $p.0 {
+            Ok(x) => {
+                swig_from_rust_to_i_type!(T, x, ret)
+                ret
+            }
+            Err(err) => {
+                err.throw(env);
+                return <swig_i_type!(T)>::jni_invalid_value();
+            }
+        };
+    };
+    ($p:f_type, unique_prefix = "/*Guarded<swig_subst_type!(T)>*/") => "/*Guarded<swig_subst_type!(T)>*/swig_f_type!(T)" "swig_foreign_from_i_type!(T, $p)";
+);
@@ -4,55 +4,2318 @@
 mod jni_c_header;
 
 use ironoxide::blocking::BlockingDeviceContext as DeviceContext;
-use ironoxide::{blocking::BlockingIronOxide as IronOxide, prelude::*};
+use ironoxide::{blocking::BlockingIronOxide, prelude::*};
+use sha2::{Digest, Sha256};
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::{
+        HashMap, HashSet,
+        hash_map::{DefaultHasher, RandomState},
+    },
     convert::TryInto,
-    hash::{Hash, Hasher},
+    hash::{BuildHasher, Hash, Hasher},
+    ops::Deref,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{self, AtomicUsize},
+        mpsc,
+    },
     time::Duration,
 };
 use time::OffsetDateTime;
 
 include!(concat!(env!("OUT_DIR"), "/lib.rs"));
 
-/// Initialize rustls-platform-verifier for Android TLS certificate verification.
-/// Called from the generated JNI_OnLoad via build.rs post-processing.
-#[cfg(all(target_os = "android", feature = "android"))]
-fn init_rustls_platform_verifier(raw_env: *mut jni_sys::JNIEnv) {
-    let env = unsafe { jni::JNIEnv::from_raw(raw_env as *mut jni::sys::JNIEnv) }
-        .expect("Failed to wrap JNIEnv");
-    let mut env = env;
-
-    // Get the application context via ActivityThread.currentApplication().
-    let activity_thread = env
-        .find_class("android/app/ActivityThread")
-        .expect("Failed to find ActivityThread class");
-    let context = env
-        .call_static_method(
-            activity_thread,
-            "currentApplication",
-            "()Landroid/app/Application;",
-            &[],
+/// Frees the bytes behind a `std::vector<uint8_t>` returned to C++ once they've been copied. See
+/// `Vec<i8>` in cpp_typemaps.rs.
+#[cfg(feature = "cpp")]
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "C" fn CByteVec_free(v: CByteVec) {
+    drop(unsafe { Vec::from_raw_parts(v.data, v.len, v.capacity) });
+}
+
+/// Frees the message of an `ErrorInfo` returned to C++ once it's been copied. See `FfiError` in
+/// cpp_typemaps.rs.
+#[cfg(feature = "cpp")]
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "C" fn CIronOxideError_free(e: CIronOxideError) {
+    drop(unsafe { Vec::from_raw_parts(e.message, e.len, e.capacity) });
+}
+
+/// Setup needed before the SDK can make requests. On Android, TLS certificates are verified
+/// through the platform's trust store, which needs a `Context` handed over by
+/// `IronOxide.initializePlatform`. Other platforms don't need any.
+///
+/// IronOxide's HTTP client always verifies certificates with `rustls-platform-verifier`, and
+/// doesn't let these bindings supply another verifier or set of roots. So there's no fallback
+/// trust store on Android: until the platform is initialized, calls that would contact the
+/// webservice fail up front instead of failing, or panicking, in the TLS handshake.
+mod platform {
+    use super::Error;
+
+    #[cfg(all(target_os = "android", feature = "android"))]
+    mod android {
+        use jni::{
+            JNIEnv,
+            objects::{JClass, JObject},
+        };
+        use std::sync::Mutex;
+
+        static INITIALIZED: Mutex<bool> = Mutex::new(false);
+
+        pub fn initialize(env: &mut JNIEnv, context: JObject) -> Result<(), String> {
+            let mut initialized = INITIALIZED.lock().unwrap_or_else(|e| e.into_inner());
+            if !*initialized {
+                rustls_platform_verifier::android::init_with_env(env, context).map_err(|e| {
+                    format!("Failed to initialize the Android certificate verifier: {e}")
+                })?;
+                *initialized = true;
+            }
+            Ok(())
+        }
+
+        pub fn is_initialized() -> bool {
+            *INITIALIZED.lock().unwrap_or_else(|e| e.into_inner())
+        }
+
+        #[unsafe(no_mangle)]
+        pub extern "system" fn Java_com_ironcorelabs_sdk_IronOxide_do_1initializePlatform(
+            mut env: JNIEnv,
+            _class: JClass,
+            context: JObject,
+        ) {
+            let raw_env = env.get_raw();
+            if let Err(err) = crate::ffi_guard::result(|| initialize(&mut env, context)) {
+                err.throw(raw_env);
+            }
+        }
+    }
+
+    /// Fail with an explanation, rather than later on in the TLS handshake, if the platform hasn't
+    /// been initialized.
+    pub fn check_initialized() -> Result<(), Error> {
+        #[cfg(all(target_os = "android", feature = "android"))]
+        if !android::is_initialized() {
+            return Err(Error::other(
+                "IronOxide can't verify TLS certificates until \
+                IronOxide.initializePlatform(Context) has been called. Android's trust store \
+                is only available through a Context, and there's no fallback trust store.",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by the operations in this file. It keeps the kind of failure, so the bindings can
+/// tell callers why a call failed without them having to read the message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+}
+
+/// Why an operation failed. A panic is reported separately, see `ffi_guard`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The operation didn't finish within the SDK-wide or per-call timeout.
+    Timeout,
+    /// The request to the IronCore webservice failed or was rejected.
+    Request,
+    /// An argument wasn't valid.
+    Validation,
+    /// Anything else, such as failing to decrypt or to write a file.
+    Other,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Error {
+        Error {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Validation, message)
+    }
+
+    pub fn other(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Other, message)
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl From<IronOxideErr> for Error {
+    fn from(err: IronOxideErr) -> Error {
+        let kind = match err {
+            IronOxideErr::OperationTimedOut { .. } => ErrorKind::Timeout,
+            IronOxideErr::RequestError { .. } | IronOxideErr::RequestServerErrors { .. } => {
+                ErrorKind::Request
+            }
+            IronOxideErr::ValidationError(..) | IronOxideErr::WrongSizeError(..) => {
+                ErrorKind::Validation
+            }
+            _ => ErrorKind::Other,
+        };
+        Error::new(kind, err.to_string())
+    }
+}
+
+/// The messages written in this file are about invalid arguments, unless they're created with
+/// another kind, so that's what they're reported as.
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::validation(message)
+    }
+}
+
+/// Panics mustn't unwind out of the generated `extern "C"` functions, which would abort the host
+/// process. Every function declared in lib.rs.in is called through a wrapper in `ffi_guarded`, which
+/// build.rs generates from the declarations, so a panic is caught and handed to the caller as an
+/// error instead.
+mod ffi_guard {
+    use super::Error;
+    #[cfg(any(feature = "java", feature = "android"))]
+    use super::*;
+    use std::{
+        any::Any,
+        panic::{self, AssertUnwindSafe},
+    };
+
+    /// Error returned through the bindings by a call into lib.rs.
+    pub enum FfiError {
+        /// The call failed with an error from lib.rs or IronOxide.
+        Failed(Error),
+        /// The call panicked, which is a bug in lib.rs or IronOxide.
+        Panicked(String),
+    }
+    impl FfiError {
+        /// The kind of failure, as the C++ `ErrorKind`: 0 for anything else, then timeout,
+        /// request, validation and internal.
+        #[cfg(feature = "cpp")]
+        pub fn kind_code(&self) -> u32 {
+            use super::ErrorKind;
+            match self {
+                FfiError::Failed(err) => match err.kind() {
+                    ErrorKind::Other => 0,
+                    ErrorKind::Timeout => 1,
+                    ErrorKind::Request => 2,
+                    ErrorKind::Validation => 3,
+                },
+                FfiError::Panicked(_) => 4,
+            }
+        }
+
+        pub fn into_message(self) -> String {
+            match self {
+                FfiError::Failed(err) => err.message,
+                FfiError::Panicked(message) => format!("IronOxide internal error: {message}"),
+            }
+        }
+
+        /// Throw the error as a Java exception: an `IronOxideInternalError` for a panic, otherwise
+        /// an `Exception` like flapigen's own `Result` typemap.
+        #[cfg(any(feature = "java", feature = "android"))]
+        pub fn throw(self, env: *mut JNIEnv) {
+            let class_name = match self {
+                FfiError::Failed(_) => c"java/lang/Exception",
+                FfiError::Panicked(_) => c"com/ironcorelabs/sdk/IronOxideInternalError",
+            };
+            let message =
+                std::ffi::CString::new(self.into_message().replace('\0', "")).unwrap_or_default();
+            unsafe {
+                // a panic may have left an exception pending, which would stop a new one being thrown
+                (**env).ExceptionClear.unwrap()(env);
+                let mut class = (**env).FindClass.unwrap()(env, class_name.as_ptr());
+                if class.is_null() {
+                    (**env).ExceptionClear.unwrap()(env);
+                    class = (**env).FindClass.unwrap()(env, c"java/lang/Error".as_ptr());
+                }
+                (**env).ThrowNew.unwrap()(env, class, message.as_ptr());
+            }
+        }
+    }
+
+    /// Result of a call that can only fail by panicking. Its JNI typemap throws an
+    /// `IronOxideInternalError` for a panic without adding `throws Exception` to the Java method.
+    /// C++ has no way to report an error except by returning it, so there these calls return a
+    /// `Result` instead.
+    #[cfg(any(feature = "java", feature = "android"))]
+    pub struct Guarded<T>(pub Result<T, FfiError>);
+
+    fn panic_message(payload: &(dyn Any + Send)) -> String {
+        payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic")
+            .to_string()
+    }
+
+    /// Run a call to one of the functions in lib.rs, turning a panic into an error.
+    pub fn result<T, E: Into<Error>>(call: impl FnOnce() -> Result<T, E>) -> Result<T, FfiError> {
+        match panic::catch_unwind(AssertUnwindSafe(call)) {
+            Ok(result) => result.map_err(|err| FfiError::Failed(err.into())),
+            Err(payload) => Err(FfiError::Panicked(panic_message(&*payload))),
+        }
+    }
+
+    /// Run a call to one of the functions in lib.rs that can't return an error.
+    pub fn infallible<T>(call: impl FnOnce() -> T) -> Result<T, FfiError> {
+        result(|| Ok::<_, Error>(call()))
+    }
+
+    /// Like `infallible`, for the JNI typemap that doesn't declare `throws Exception`.
+    #[cfg(any(feature = "java", feature = "android"))]
+    pub fn guarded<T>(call: impl FnOnce() -> T) -> Guarded<T> {
+        Guarded(infallible(call))
+    }
+
+    /// Exported as `IronOxide.panicForTest` so the tests can check how panics are reported. It's
+    /// only built with the `panic-for-test` feature.
+    #[cfg(feature = "panic-for-test")]
+    pub fn panic_for_test(message: &str) -> Result<usize, String> {
+        panic!("{message}")
+    }
+}
+use ffi_guard::FfiError;
+#[cfg(any(feature = "java", feature = "android"))]
+use ffi_guard::Guarded;
+
+/// Wrappers for the functions declared in lib.rs.in, which build.rs generates and points the
+/// declarations at. See `ffi_guard`.
+#[allow(non_snake_case, clippy::too_many_arguments, clippy::redundant_closure)]
+mod ffi_guarded {
+    use super::*;
+    include!(concat!(env!("OUT_DIR"), "/ffi_guarded.rs"));
+}
+
+/// BlockingIronOxide shared behind an `Arc` so that an operation can be handed off to another
+/// thread when the caller asks for a per-call timeout, along with the retry policy from the
+/// `IronOxideConfig` it was initialized with.
+#[derive(Clone)]
+pub struct IronOxide {
+    sdk: Arc<BlockingIronOxide>,
+    retry_policy: RetryPolicy,
+}
+impl IronOxide {
+    fn new(sdk: BlockingIronOxide, retry_policy: &RetryPolicy) -> IronOxide {
+        IronOxide {
+            sdk: Arc::new(sdk),
+            retry_policy: retry_policy.clone(),
+        }
+    }
+
+    /// Run a network operation against the SDK, retrying it as allowed by the retry policy.
+    /// `idempotent` says whether the operation is safe to repeat if an earlier attempt may
+    /// have reached the webservice.
+    fn retry<T, F>(&self, idempotent: bool, mut op: F) -> ironoxide::Result<T>
+    where
+        F: FnMut(&BlockingIronOxide) -> ironoxide::Result<T>,
+    {
+        self.retry_policy.run(idempotent, || op(&self.sdk))
+    }
+
+    pub fn clear_policy_cache(&self) -> usize {
+        self.sdk.clear_policy_cache()
+    }
+}
+
+impl Deref for IronOxide {
+    type Target = BlockingIronOxide;
+    fn deref(&self) -> &BlockingIronOxide {
+        &self.sdk
+    }
+}
+
+/// IronOxide's configuration along with the settings that are handled by these bindings
+/// rather than by IronOxide itself.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IronOxideConfig {
+    sdk: ironoxide::config::IronOxideConfig,
+    retry_policy: RetryPolicy,
+}
+
+/// How network operations that fail with a transient error are retried.
+///
+/// The delay before retry `n` is chosen at random between zero and
+/// `initial_backoff * 2^(n - 1)`, capped at `max_backoff` ("full jitter"), so that many
+/// clients failing at once don't all retry in lockstep.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_server_errors: bool,
+    retry_connection_errors: bool,
+    retry_timeouts: bool,
+    idempotent_only: bool,
+}
+impl Default for RetryPolicy {
+    /// Never retry, which is how the SDK behaved before retry policies were added.
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            retry_server_errors: true,
+            retry_connection_errors: true,
+            retry_timeouts: true,
+            idempotent_only: true,
+        }
+    }
+}
+impl RetryPolicy {
+    fn run<T>(
+        &self,
+        idempotent: bool,
+        mut op: impl FnMut() -> ironoxide::Result<T>,
+    ) -> ironoxide::Result<T> {
+        let max_attempts = if idempotent || !self.idempotent_only {
+            self.max_attempts.max(1)
+        } else {
+            1
+        };
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Err(e) if attempt < max_attempts && self.is_retryable(&e) => {
+                    std::thread::sleep(self.backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn is_retryable(&self, err: &IronOxideErr) -> bool {
+        match err {
+            IronOxideErr::RequestError { http_status, .. } => {
+                self.is_retryable_status(*http_status)
+            }
+            IronOxideErr::RequestServerErrors {
+                http_status: Some(status),
+                ..
+            } => self.is_retryable_status(Some(*status)),
+            IronOxideErr::OperationTimedOut { .. } => self.retry_timeouts,
+            _ => false,
+        }
+    }
+
+    /// Whether a failed request with this HTTP status is worth trying again.
+    fn is_retryable_status(&self, http_status: Option<u16>) -> bool {
+        match http_status {
+            // 5xx responses, along with rate limiting, are worth trying again
+            Some(status) => self.retry_server_errors && (status >= 500 || status == 429),
+            // a request error without an HTTP status means no response was received
+            None => self.retry_connection_errors,
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_backoff);
+        // RandomState is seeded differently every time it's created, which is plenty for jitter
+        let random = RandomState::new().build_hasher().finish();
+        ceiling.mul_f64(random as f64 / u64::MAX as f64)
+    }
+}
+
+/// Most worker threads that can be running `with_timeout` operations at once. A call that times
+/// out leaves its worker running until the operation finishes or hits the SDK-wide timeout, so
+/// without a limit a slow webservice would leave a growing pile of threads behind.
+const MAX_TIMEOUT_WORKERS: usize = 64;
+static TIMEOUT_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// One of the `MAX_TIMEOUT_WORKERS` slots, held by a worker thread until it finishes.
+struct TimeoutWorker;
+impl TimeoutWorker {
+    fn acquire() -> Result<TimeoutWorker, Error> {
+        TIMEOUT_WORKERS
+            .fetch_update(atomic::Ordering::AcqRel, atomic::Ordering::Acquire, |n| {
+                (n < MAX_TIMEOUT_WORKERS).then_some(n + 1)
+            })
+            .map(|_| TimeoutWorker)
+            .map_err(|_| {
+                Error::other(format!(
+                    "Too many calls with a timeout are still running. At most {MAX_TIMEOUT_WORKERS} can run at once."
+                ))
+            })
+    }
+}
+impl Drop for TimeoutWorker {
+    fn drop(&mut self) {
+        TIMEOUT_WORKERS.fetch_sub(1, atomic::Ordering::AcqRel);
+    }
+}
+
+/// Run `op`, giving up and returning an error if it hasn't finished within `timeout`.
+/// If `timeout` is `None` the operation is run on the calling thread.
+///
+/// The SDK-wide timeout from `IronOxideConfig` still applies to the operation itself, so
+/// a per-call timeout can only shorten it. Timing out doesn't cancel the operation: it keeps
+/// running on its worker thread, which is why only idempotent operations are offered with a
+/// timeout.
+fn with_timeout<T, F>(timeout: Option<&Duration>, op: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> ironoxide::Result<T> + Send + 'static,
+{
+    match timeout {
+        None => Ok(op()?),
+        Some(duration) => {
+            let worker = TimeoutWorker::acquire()?;
+            let (tx, rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _worker = worker;
+                // the receiver is gone if the caller already timed out, so there's nobody to tell
+                let _ = tx.send(op());
+            });
+            match rx.recv_timeout(*duration) {
+                Ok(result) => Ok(result?),
+                Err(mpsc::RecvTimeoutError::Timeout) => Err(timed_out(duration)),
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    Err(Error::other("Operation ended without producing a result"))
+                }
+            }
+        }
+    }
+}
+
+/// Like `with_timeout`, but for operations that write their output to `destination_path`. They
+/// share the same limit on worker threads.
+///
+/// With a timeout, `op` is given a temporary path next to the destination and its output is
+/// only moved into place if the caller is still waiting for it. If the caller has timed out by
+/// the time `op` finishes, the temporary file is deleted instead, so a call that timed out never
+/// leaves a partially written (or, for decryption, any) output behind.
+fn with_file_timeout<T, F>(
+    timeout: Option<&Duration>,
+    destination_path: &str,
+    op: F,
+) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce(&str) -> ironoxide::Result<T> + Send + 'static,
+{
+    let Some(duration) = timeout else {
+        return Ok(op(destination_path)?);
+    };
+    let destination_path = destination_path.to_string();
+    let partial_path = format!(
+        "{}.{}-{:x}.partial",
+        destination_path,
+        std::process::id(),
+        RandomState::new().build_hasher().finish()
+    );
+    let worker = TimeoutWorker::acquire()?;
+    let abandoned = Arc::new(Mutex::new(false));
+    let worker_abandoned = abandoned.clone();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _worker = worker;
+        let result: Result<T, Error> = op(&partial_path).map_err(Into::into);
+        // hold the lock until the result is sent so the caller can't time out in between
+        let abandoned = worker_abandoned
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let result = match result {
+            Ok(value) if !*abandoned => std::fs::rename(&partial_path, &destination_path)
+                .map(|_| value)
+                .map_err(|e| {
+                    Error::other(format!(
+                        "Failed to move output to {}: {}",
+                        destination_path, e
+                    ))
+                }),
+            other => other,
+        };
+        if *abandoned || result.is_err() {
+            let _ = std::fs::remove_file(&partial_path);
+        }
+        let _ = tx.send(result);
+    });
+    match rx.recv_timeout(*duration) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            *abandoned.lock().unwrap_or_else(PoisonError::into_inner) = true;
+            // the operation may have finished between timing out and taking the lock
+            rx.try_recv().unwrap_or_else(|_| Err(timed_out(duration)))
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err(Error::other("Operation ended without producing a result"))
+        }
+    }
+}
+
+fn timed_out(duration: &Duration) -> Error {
+    Error::new(
+        ErrorKind::Timeout,
+        format!("Operation timed out after {}ms", duration.as_millis()),
+    )
+}
+
+/// Run `op` on every item using at most `concurrency` threads, returning the results in the
+/// same order as `items`.
+fn run_bounded<T, R, F>(items: &[T], concurrency: usize, op: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let (next, op) = (&next, &op);
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.min(items.len()) {
+            let tx = tx.clone();
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, atomic::Ordering::Relaxed);
+                    let Some(item) = items.get(i) else { break };
+                    // the receiver outlives every worker, so sending can't fail
+                    let _ = tx.send((i, op(item)));
+                }
+            });
+        }
+    });
+    drop(tx);
+    let mut results: Vec<(usize, R)> = rx.into_iter().collect();
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Each unit of concurrency is a thread making requests, so a caller can't start an unbounded
+/// number of them.
+const MAX_CONCURRENCY: usize = 64;
+
+fn check_concurrency(max_concurrency: usize) -> Result<(), Error> {
+    if max_concurrency == 0 {
+        Err(Error::validation("maxConcurrency must be at least 1."))
+    } else if max_concurrency > MAX_CONCURRENCY {
+        Err(Error::validation(format!(
+            "maxConcurrency must be at most {MAX_CONCURRENCY}."
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn hash<T: Hash>(t: &T) -> i32 {
+    let mut s = DefaultHasher::new();
+    t.hash(&mut s);
+    s.finish() as i32
+}
+
+pub fn eq<T: PartialEq>(t: &T, other: &T) -> bool {
+    t.eq(other)
+}
+
+pub fn describe<T: Describe>(t: &T) -> String {
+    t.describe()
+}
+
+/// What the generated `toString()` (Java) and `operator<<` (C++) show. build.rs adds them to every
+/// class, so a new class doesn't compile until it's given an implementation here. Most use their
+/// `Debug` output. Anything holding key material or decrypted data shows that it's there without
+/// showing it.
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+macro_rules! describe_with_debug {
+    ($($t:ty),* $(,)?) => {
+        $(impl Describe for $t {
+            fn describe(&self) -> String {
+                format!("{self:?}")
+            }
+        })*
+    };
+}
+
+describe_with_debug!(
+    PublicKey,
+    UserId,
+    GroupId,
+    UserOrGroupId,
+    GroupName,
+    NullableBoolean,
+    DocumentId,
+    DocumentName,
+    DocumentHeader,
+    DeviceId,
+    JwtClaims,
+    UserUpdatePrivateKeyResult,
+    UserUpdateResult,
+    PrivateKeyRotationCheckResult,
+    RotateAllResult,
+    EncryptedPrivateKey,
+    DeviceName,
+    VisibleUser,
+    VisibleGroup,
+    UserAccessErr,
+    GroupAccessErr,
+    UserWithKey,
+    GroupUserList,
+    UserCreateResult,
+    UserResult,
+    UserDevice,
+    DeviceCreateOptsBuilder,
+    DeviceCreateOpts,
+    UserCreateOptsBuilder,
+    UserCreateOpts,
+    UserDeviceListResult,
+    DeviceDeleteOutcome,
+    DeviceDeleteReport,
+    OffboardChange,
+    OffboardReport,
+    GroupMetaResult,
+    GroupCreateResult,
+    GroupListResult,
+    GroupListFilter,
+    GroupNameResolution,
+    GroupSyncChangeResult,
+    GroupSyncResult,
+    GroupGetResult,
+    GroupUpdatePrivateKeyResult,
+    GroupCreateOptsBuilder,
+    GroupCreateOpts,
+    GroupAccessEditErr,
+    GroupAccessEditResult,
+    Category,
+    Sensitivity,
+    DataSubject,
+    PolicyGrant,
+    DocumentEncryptOptsBuilder,
+    DocumentEncryptOpts,
+    DocumentListMeta,
+    DocumentListResult,
+    DocumentListFilter,
+    DocumentListPage,
+    DocumentListCursor,
+    DocumentMetadataResult,
+    AccessReportUser,
+    AccessReportGroup,
+    AccessReport,
+    DocumentEncryptDirectResult,
+    DocumentDecryptDirectResult,
+    DocumentMetadataUnmanagedResult,
+    EdekRecipient,
+    EdekInfo,
+    DocumentAccessUnmanagedResult,
+    SucceededResult,
+    FailedResult,
+    DocumentAccessResult,
+    EncryptedDeks,
+    DocumentBulkAccessEntry,
+    DocumentBulkAccessResult,
+    DocumentBulkAccessUnmanagedEntry,
+    DocumentBulkAccessUnmanagedResult,
+    DocumentFileEncryptResult,
+    DocumentFileEncryptUnmanagedResult,
+    DocumentFileDecryptResult,
+    DocumentFileDecryptUnmanagedResult,
+    PolicyCachingConfig,
+    IronOxideConfigBuilder,
+    IronOxideConfig,
+    RetryPolicy,
+    Duration,
+    EncryptedBlindIndexSalt,
+);
+
+impl Describe for PrivateKey {
+    fn describe(&self) -> String {
+        "PrivateKey(<redacted>)".to_string()
+    }
+}
+
+impl Describe for DeviceSigningKeyPair {
+    fn describe(&self) -> String {
+        "DeviceSigningKeyPair(<redacted>)".to_string()
+    }
+}
+
+impl Describe for DeviceContext {
+    fn describe(&self) -> String {
+        format!(
+            "DeviceContext {{ account_id: {:?}, segment_id: {}, device_private_key: <redacted>, \
+            signing_private_key: <redacted> }}",
+            self.account_id(),
+            self.segment_id()
         )
-        .expect("Failed to call currentApplication")
-        .l()
-        .expect("currentApplication did not return an object");
+    }
+}
+
+impl Describe for DeviceAddResult {
+    fn describe(&self) -> String {
+        format!(
+            "DeviceAddResult {{ account_id: {:?}, segment_id: {}, device_id: {:?}, name: {:?}, \
+            created: {:?}, last_updated: {:?}, device_private_key: <redacted>, \
+            signing_private_key: <redacted> }}",
+            self.account_id(),
+            self.segment_id(),
+            self.device_id(),
+            self.name(),
+            self.created(),
+            self.last_updated()
+        )
+    }
+}
+
+impl Describe for Jwt {
+    fn describe(&self) -> String {
+        format!("Jwt {{ claims: {:?}, token: <redacted> }}", self.claims())
+    }
+}
+
+impl Describe for DocumentDecryptResult {
+    fn describe(&self) -> String {
+        format!(
+            "DocumentDecryptResult {{ id: {:?}, name: {:?}, created: {:?}, last_updated: {:?}, \
+            decrypted_data: <{} bytes redacted> }}",
+            self.id(),
+            self.name(),
+            self.created(),
+            self.last_updated(),
+            self.decrypted_data().len()
+        )
+    }
+}
+
+impl Describe for DocumentDecryptUnmanagedResult {
+    fn describe(&self) -> String {
+        format!(
+            "DocumentDecryptUnmanagedResult {{ id: {:?}, access_via: {:?}, \
+            decrypted_data: <{} bytes redacted> }}",
+            self.id(),
+            self.access_via(),
+            self.decrypted_data().len()
+        )
+    }
+}
+
+// Encrypted data isn't secret, but it's far too long to be useful in a log.
+impl Describe for DocumentEncryptResult {
+    fn describe(&self) -> String {
+        format!(
+            "DocumentEncryptResult {{ id: {:?}, name: {:?}, created: {:?}, last_updated: {:?}, \
+            grants: {:?}, access_errs: {:?}, encrypted_data: <{} bytes> }}",
+            self.id(),
+            self.name(),
+            self.created(),
+            self.last_updated(),
+            self.grants(),
+            self.access_errs(),
+            self.encrypted_data().len()
+        )
+    }
+}
+
+impl Describe for DocumentEncryptUnmanagedResult {
+    fn describe(&self) -> String {
+        format!(
+            "DocumentEncryptUnmanagedResult {{ id: {:?}, grants: {:?}, access_errs: {:?}, \
+            encrypted_data: <{} bytes>, encrypted_deks: <{} bytes> }}",
+            self.id(),
+            self.grants(),
+            self.access_errs(),
+            self.encrypted_data().len(),
+            self.encrypted_deks().len()
+        )
+    }
+}
+
+impl Describe for BlindIndexSearch {
+    fn describe(&self) -> String {
+        "BlindIndexSearch(<redacted>)".to_string()
+    }
+}
+
+impl Describe for IronOxide {
+    fn describe(&self) -> String {
+        let device = self.device();
+        format!(
+            "IronOxide {{ account_id: {:?}, segment_id: {}, retry_policy: {:?} }}",
+            device.account_id(),
+            device.segment_id(),
+            self.retry_policy
+        )
+    }
+}
+
+impl Describe for InitAndRotationCheck {
+    fn describe(&self) -> String {
+        format!(
+            "InitAndRotationCheck {{ sdk: {}, rotation: {:?} }}",
+            self.sdk.describe(),
+            self.rotation
+        )
+    }
+}
+
+/// What `toJson()` writes and `fromJson` reads. The schema is part of the API: fields may be added
+/// to it, but existing ones keep their names and meanings. Keys are camelCase, ids and names are
+/// strings (a `DeviceId` is a number), times are Unix milliseconds, durations are milliseconds in a
+/// field whose name ends with `Millis`, bytes are base64 and optional values that aren't set are
+/// `null`. `fromJson` ignores fields it doesn't know and treats missing and `null` fields as unset.
+///
+/// Classes holding key material or decrypted data don't have a `toJson()`.
+mod json {
+    use super::*;
+    use crate::document_access_change_result::DocumentAccessChange;
+    use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+    use serde_json::{Map, Value, json};
+
+    pub trait ToJson {
+        fn to_json(&self) -> Value;
+    }
+
+    pub trait FromJson: Sized {
+        fn from_json(json: &Value) -> Result<Self, String>;
+    }
+
+    pub fn from_str<T: FromJson>(json: &str) -> Result<T, String> {
+        let value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {e}"))?;
+        T::from_json(&value)
+    }
+
+    /// The fields of a JSON object being read by `FromJson`.
+    struct Fields<'a>(&'a Map<String, Value>);
+
+    impl<'a> Fields<'a> {
+        fn new(json: &'a Value, type_name: &str) -> Result<Fields<'a>, String> {
+            json.as_object()
+                .map(Fields)
+                .ok_or_else(|| format!("{type_name} must be a JSON object."))
+        }
+
+        fn get<T: FromJson>(&self, key: &str) -> Result<Option<T>, String> {
+            match self.0.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(value) => T::from_json(value)
+                    .map(Some)
+                    .map_err(|e| format!("{key}: {e}")),
+            }
+        }
+    }
+
+    fn encode_bytes(bytes: &[u8]) -> Value {
+        json!(BASE64.encode(bytes))
+    }
+
+    fn decode_bytes(json: &Value) -> Result<Vec<u8>, String> {
+        BASE64
+            .decode(String::from_json(json)?)
+            .map_err(|e| format!("must be base64: {e}"))
+    }
+
+    /// Types serde_json already writes the way the schema wants.
+    macro_rules! to_json_with_serde {
+        ($($t:ty),*) => {
+            $(impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    json!(self)
+                }
+            })*
+        };
+    }
+
+    /// Types written as a single value taken from them, such as the string an ID holds.
+    macro_rules! to_json_as {
+        ($($t:ty => $value:expr;)*) => {
+            $(impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    ToJson::to_json(&$value(self))
+                }
+            })*
+        };
+    }
+
+    /// Enums written as the name of their variant.
+    macro_rules! to_json_enum {
+        ($($t:ident { $($variant:ident => $name:literal),* $(,)? })*) => {
+            $(impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    json!(match self {
+                        $($t::$variant => $name),*
+                    })
+                }
+            })*
+        };
+    }
+
+    /// Types written as a JSON object. `$r` is bound to the value being written, and each key is
+    /// written with the `ToJson` of its expression.
+    macro_rules! to_json_object {
+        ($($(#[$meta:meta])* $t:ty => |$r:ident| { $($key:literal: $value:expr),* $(,)? })*) => {
+            $($(#[$meta])*
+            impl ToJson for $t {
+                fn to_json(&self) -> Value {
+                    let $r = self;
+                    let mut object = Map::new();
+                    $(object.insert($key.to_string(), ToJson::to_json(&$value));)*
+                    Value::Object(object)
+                }
+            })*
+        };
+    }
+
+    /// Types written as a string and read back through their `validate` function.
+    macro_rules! from_json_validated {
+        ($($t:ty => $validate:path;)*) => {
+            $(impl FromJson for $t {
+                fn from_json(json: &Value) -> Result<$t, String> {
+                    $validate(&String::from_json(json)?)
+                }
+            })*
+        };
+    }
+
+    /// Types read from a JSON object one field at a time. A field that isn't set keeps the value it
+    /// has in the type's `default()`. Options are read into their builder, so like the constructors
+    /// this doesn't check them the way `build()` does.
+    macro_rules! from_json_fields {
+        ($($t:ident $(from $builder:ident)? { $($field:ident: $key:literal),* $(,)? })*) => {
+            $(impl FromJson for $t {
+                fn from_json(json: &Value) -> Result<$t, String> {
+                    let fields = Fields::new(json, stringify!($t))?;
+                    from_json_fields!(@read fields, $t $(from $builder)? { $($field: $key),* })
+                }
+            })*
+        };
+        (@read $fields:ident, $t:ident { $($field:ident: $key:literal),* }) => {{
+            let default = $t::default();
+            Ok($t {
+                $($field: $fields.get($key)?.unwrap_or(default.$field)),*
+            })
+        }};
+        (@read $fields:ident, $t:ident from $builder:ident {
+            $($field:ident: $key:literal),*
+        }) => {{
+            let default = $builder::default();
+            Ok($builder {
+                $($field: $fields.get($key)?.unwrap_or(default.$field)),*
+            }
+            .into())
+        }};
+    }
+
+    impl ToJson for Value {
+        fn to_json(&self) -> Value {
+            self.clone()
+        }
+    }
+
+    impl<T: ToJson + ?Sized> ToJson for &T {
+        fn to_json(&self) -> Value {
+            (**self).to_json()
+        }
+    }
+
+    impl<T: ToJson> ToJson for Option<T> {
+        fn to_json(&self) -> Value {
+            self.as_ref().map_or(Value::Null, ToJson::to_json)
+        }
+    }
+
+    impl<T: ToJson> ToJson for [T] {
+        fn to_json(&self) -> Value {
+            Value::Array(self.iter().map(ToJson::to_json).collect())
+        }
+    }
+
+    impl<T: ToJson> ToJson for Vec<T> {
+        fn to_json(&self) -> Value {
+            self.as_slice().to_json()
+        }
+    }
+
+    to_json_with_serde!(bool, str, String, u8, u32, u64, usize, i64);
+
+    to_json_as! {
+        OffsetDateTime => unix_millis;
+        Duration => duration::get_millis;
+        UserId => UserId::id;
+        GroupId => GroupId::id;
+        DocumentId => DocumentId::id;
+        DeviceId => device_id::id;
+        GroupName => GroupName::name;
+        DocumentName => DocumentName::name;
+        DeviceName => DeviceName::name;
+        Category => category::value;
+        Sensitivity => sensitivity::value;
+        DataSubject => data_subject::value;
+    }
+
+    impl ToJson for PublicKey {
+        fn to_json(&self) -> Value {
+            encode_bytes(i8_conv(&public_key::as_bytes(self)))
+        }
+    }
+
+    impl ToJson for EncryptedDeks {
+        fn to_json(&self) -> Value {
+            encode_bytes(&self.0)
+        }
+    }
+
+    impl ToJson for GroupUserList {
+        fn to_json(&self) -> Value {
+            self.0.to_json()
+        }
+    }
+
+    to_json_enum! {
+        AssociationType {
+            Owner => "owner",
+            FromUser => "fromUser",
+            FromGroup => "fromGroup",
+        }
+        GroupSyncChange {
+            AddAdmin => "addAdmin",
+            RemoveAdmin => "removeAdmin",
+            AddMember => "addMember",
+            RemoveMember => "removeMember",
+        }
+        OffboardAction {
+            RemoveGroupMember => "removeGroupMember",
+            RemoveGroupAdmin => "removeGroupAdmin",
+            RevokeDocumentAccess => "revokeDocumentAccess",
+        }
+    }
+
+    to_json_object! {
+        EncryptedBlindIndexSalt => |s| {
+            "encryptedDeks": encode_bytes(&s.encrypted_deks),
+            "encryptedSaltBytes": encode_bytes(&s.encrypted_salt_bytes),
+        }
+        UserOrGroupId => |u| {
+            "id": u.id(),
+            "type": if u.is_user() { "user" } else { "group" },
+        }
+        PolicyGrant => |p| {
+            "category": policy_grant::category(p),
+            "sensitivity": policy_grant::sensitivity(p),
+            "dataSubject": policy_grant::data_subject(p),
+            "substituteId": policy_grant::substitute_id(p),
+        }
+        DocumentEncryptOpts => |o| {
+            "id": o.settings.id,
+            "name": o.settings.name,
+            "grantToAuthor": o.settings.grant_to_author,
+            "userGrants": o.settings.user_grants,
+            "groupGrants": o.settings.group_grants,
+            "policyGrant": o.settings.policy_grant,
+        }
+        GroupCreateOpts => |o| {
+            "id": o.settings.id,
+            "name": o.settings.name,
+            "addAsAdmin": o.settings.add_as_admin,
+            "addAsMember": o.settings.add_as_member,
+            "owner": o.settings.owner,
+            "admins": o.settings.admins,
+            "members": o.settings.members,
+            "needsRotation": o.settings.needs_rotation,
+        }
+        UserCreateOpts => |o| { "needsRotation": o.settings.needs_rotation }
+        DeviceCreateOpts => |o| { "name": o.settings.name }
+        DocumentListFilter => |f| {
+            "includeOwner": f.include_owner,
+            "includeFromUser": f.include_from_user,
+            "includeFromGroup": f.include_from_group,
+            "createdAfter": f.created_after,
+            "createdBefore": f.created_before,
+            "updatedAfter": f.updated_after,
+            "updatedBefore": f.updated_before,
+            "namePrefix": f.name_prefix,
+        }
+        GroupListFilter => |f| {
+            "adminOnly": f.admin_only,
+            "memberOnly": f.member_only,
+            "needsRotationOnly": f.needs_rotation_only,
+            "name": f.name,
+        }
+        PolicyCachingConfig => |c| { "maxEntries": c.max_entries }
+        RetryPolicy => |p| {
+            "maxAttempts": p.max_attempts,
+            "initialBackoffMillis": p.initial_backoff,
+            "maxBackoffMillis": p.max_backoff,
+            "retryServerErrors": p.retry_server_errors,
+            "retryConnectionErrors": p.retry_connection_errors,
+            "retryTimeouts": p.retry_timeouts,
+            "idempotentOnly": p.idempotent_only,
+        }
+        IronOxideConfig => |c| {
+            "policyCaching": c.sdk.policy_caching,
+            "sdkOperationTimeoutMillis": c.sdk.sdk_operation_timeout,
+            "retryPolicy": c.retry_policy,
+        }
+        UserWithKey => |u| {
+            "id": u.user(),
+            "publicKey": u.public_key(),
+        }
+        VisibleUser => |u| { "id": visible_user::id(u) }
+        VisibleGroup => |g| {
+            "id": visible_group::id(g),
+            "name": visible_group::name(g),
+        }
+        UserCreateResult => |r| {
+            "userPublicKey": user_create_result::user_public_key(r),
+            "needsRotation": user_create_result::needs_rotation(r),
+        }
+        UserUpdateResult => |r| {
+            "userPublicKey": user_update_result::user_public_key(r),
+            "needsRotation": user_update_result::needs_rotation(r),
+        }
+        UserResult => |r| {
+            "accountId": user_result::account_id(r),
+            "segmentId": user_result::segment_id(r),
+            "userPublicKey": user_result::user_public_key(r),
+            "needsRotation": user_result::needs_rotation(r),
+        }
+        PrivateKeyRotationCheckResult => |r| {
+            "userRotationNeeded": private_key_rotation_check_result::user_rotation_needed(r),
+            "groupRotationNeeded": private_key_rotation_check_result::group_rotation_needed(r),
+        }
+        UserDevice => |d| {
+            "id": user_device::id(d),
+            "name": user_device::name(d),
+            "created": user_device::created(d),
+            "lastUpdated": user_device::last_updated(d),
+            "isCurrentDevice": d.is_current_device(),
+        }
+        UserDeviceListResult => |r| { "result": user_device_list_result::result(r) }
+        DeviceDeleteOutcome => |o| {
+            "device": o.device,
+            "error": o.error,
+        }
+        DeviceDeleteReport => |r| { "result": r.0 }
+        OffboardChange => |c| {
+            "action": offboard_change::action(c),
+            "groupId": offboard_change::group_id(c),
+            "documentId": offboard_change::document_id(c),
+            "error": c.error,
+        }
+        OffboardReport => |r| {
+            "user": r.user,
+            "dryRun": r.dry_run,
+            "changes": r.changes,
+            "lookupErrors": r.lookup_errors,
+        }
+        GroupMetaResult => |g| {
+            "id": group_meta_result::id(g),
+            "name": group_meta_result::name(g),
+            "isAdmin": g.is_admin(),
+            "isMember": g.is_member(),
+            "created": group_meta_result::created(g),
+            "lastUpdated": group_meta_result::last_updated(g),
+            "needsRotation": group_meta_result::needs_rotation(g).map(|b| b.boolean()),
+        }
+        GroupCreateResult => |g| {
+            "id": group_create_result::id(g),
+            "name": group_create_result::name(g),
+            "groupMasterPublicKey": group_create_result::group_master_public_key(g),
+            "isAdmin": g.is_admin(),
+            "isMember": g.is_member(),
+            "owner": group_create_result::owner(g),
+            "adminList": group_create_result::admin_list(g),
+            "memberList": group_create_result::member_list(g),
+            "created": group_create_result::created(g),
+            "lastUpdated": group_create_result::last_updated(g),
+            "needsRotation": group_create_result::needs_rotation(g).map(|b| b.boolean()),
+        }
+        GroupGetResult => |g| {
+            "id": group_get_result::id(g),
+            "name": group_get_result::name(g),
+            "groupMasterPublicKey": group_get_result::group_master_public_key(g),
+            "isAdmin": g.is_admin(),
+            "isMember": g.is_member(),
+            "adminList": group_get_result::admin_list(g),
+            "memberList": group_get_result::member_list(g),
+            "created": group_get_result::created(g),
+            "lastUpdated": group_get_result::last_updated(g),
+            "needsRotation": group_get_result::needs_rotation(g).map(|b| b.boolean()),
+        }
+        GroupListResult => |r| { "result": group_list_result::result(r) }
+        GroupNameResolution => |r| { "matches": r.0 }
+        GroupSyncChangeResult => |r| {
+            "user": r.user,
+            "change": r.change,
+            "error": r.error,
+        }
+        GroupSyncResult => |r| {
+            "dryRun": r.dry_run,
+            "changes": r.changes,
+        }
+        GroupUpdatePrivateKeyResult => |r| {
+            "id": group_update_private_key_result::id(r),
+            "needsRotation": group_update_private_key_result::needs_rotation(r),
+        }
+        GroupAccessEditErr => |e| {
+            "user": access_edit_failure::user(e),
+            "error": access_edit_failure::error(e),
+        }
+        GroupAccessEditResult => |r| {
+            "succeeded": group_access_edit_result::succeeded(r),
+            "failed": group_access_edit_result::failed(r),
+        }
+        DocumentHeader => |h| {
+            "version": h.version,
+            "id": h.id,
+            "segmentId": h.segment_id,
+            "length": h.length,
+        }
+        DocumentListMeta => |d| {
+            "id": document_list_meta::id(d),
+            "name": document_list_meta::name(d),
+            "associationType": document_list_meta::association_type(d),
+            "created": document_list_meta::created(d),
+            "lastUpdated": document_list_meta::last_updated(d),
+        }
+        DocumentListResult => |r| { "result": document_list_result::result(r) }
+        DocumentListPage => |p| {
+            "result": p.result(),
+            "hasNextPage": p.has_next_page(),
+        }
+        DocumentMetadataResult => |d| {
+            "id": document_metadata_result::id(d),
+            "name": document_metadata_result::name(d),
+            "associationType": document_metadata_result::association_type(d),
+            "visibleToUsers": document_metadata_result::visible_to_users(d),
+            "visibleToGroups": document_metadata_result::visible_to_groups(d),
+            "created": document_metadata_result::created(d),
+            "lastUpdated": document_metadata_result::last_updated(d),
+        }
+        DocumentMetadataUnmanagedResult => |d| {
+            "id": document_metadata_unmanaged_result::id(d),
+            "visibleToUsers": document_metadata_unmanaged_result::visible_to_users(d),
+            "visibleToGroups": document_metadata_unmanaged_result::visible_to_groups(d),
+        }
+        /// Keeps the schema `AccessReport.toJson()` had before every class had one.
+        AccessReportUser => |u| {
+            "id": u.id,
+            "direct": u.direct,
+            "viaGroups": u.via_groups,
+        }
+        AccessReportGroup => |g| {
+            "id": g.id,
+            "name": g.name,
+            "membersVisible": g.members_visible,
+            "error": g.error,
+        }
+        AccessReport => |r| {
+            "documentId": r.document_id,
+            "complete": access_report::is_complete(r),
+            "users": r.users,
+            "groups": r.groups,
+        }
+        SucceededResult => |r| {
+            "users": r.users(),
+            "groups": r.groups(),
+        }
+        UserAccessErr => |e| {
+            "id": e.id(),
+            "error": e.err(),
+        }
+        GroupAccessErr => |e| {
+            "id": e.id(),
+            "error": e.err(),
+        }
+        FailedResult => |r| {
+            "users": r.users(),
+            "groups": r.groups(),
+        }
+        DocumentAccessResult => |r| {
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentAccessUnmanagedResult => |r| {
+            "accessVia": document_access_unmanaged_result::access_via(r),
+            "encryptedDeks":
+                encode_bytes(i8_conv(&document_access_unmanaged_result::encrypted_deks(r))),
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentBulkAccessEntry => |e| {
+            "id": e.id,
+            "error": e.error,
+            "changed": e.changed(),
+            "errors": e.errors(),
+        }
+        DocumentBulkAccessResult => |r| { "result": r.0 }
+        DocumentBulkAccessUnmanagedEntry => |e| {
+            "encryptedDeks": e.encrypted_deks,
+            "error": e.error,
+            "changed": e.changed(),
+            "errors": e.errors(),
+        }
+        DocumentBulkAccessUnmanagedResult => |r| { "result": r.0 }
+        /// The encrypted bytes are base64 so a result can be stored or sent as it is.
+        DocumentEncryptResult => |r| {
+            "id": document_encrypt_result::id(r),
+            "name": document_encrypt_result::name(r),
+            "encryptedData": encode_bytes(r.encrypted_data()),
+            "created": document_encrypt_result::created(r),
+            "lastUpdated": document_encrypt_result::last_updated(r),
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentEncryptUnmanagedResult => |r| {
+            "id": document_encrypt_unmanaged_result::id(r),
+            "encryptedData": encode_bytes(r.encrypted_data()),
+            "encryptedDeks": encode_bytes(r.encrypted_deks()),
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentEncryptDirectResult => |r| {
+            "id": r.id,
+            "length": r.length,
+            "encryptedDeks": r.encrypted_deks,
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentDecryptDirectResult => |r| {
+            "id": r.id,
+            "length": r.length,
+        }
+        DocumentFileEncryptResult => |r| {
+            "id": document_file_encrypt_result::id(r),
+            "name": document_file_encrypt_result::name(r),
+            "created": document_file_encrypt_result::created(r),
+            "lastUpdated": document_file_encrypt_result::last_updated(r),
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentFileEncryptUnmanagedResult => |r| {
+            "id": document_file_encrypt_unmanaged_result::id(r),
+            "encryptedDeks":
+                encode_bytes(i8_conv(&document_file_encrypt_unmanaged_result::encrypted_deks(r))),
+            "changed": r.changed(),
+            "errors": r.errors(),
+        }
+        DocumentFileDecryptResult => |r| {
+            "id": document_file_decrypt_result::id(r),
+            "name": document_file_decrypt_result::name(r),
+        }
+        DocumentFileDecryptUnmanagedResult => |r| {
+            "id": document_file_decrypt_unmanaged_result::id(r),
+            "accessVia": document_file_decrypt_unmanaged_result::access_via(r),
+        }
+        EdekRecipient => |r| {
+            "userOrGroup": r.id,
+            "publicKey": r.public_key,
+            "fingerprint": edek_info::fingerprint(r),
+        }
+        EdekInfo => |i| {
+            "id": i.id,
+            "segmentId": i.segment_id,
+            "recipients": i.recipients,
+        }
+    }
+
+    impl FromJson for bool {
+        fn from_json(json: &Value) -> Result<bool, String> {
+            json.as_bool()
+                .ok_or_else(|| "must be true or false.".to_string())
+        }
+    }
+
+    impl FromJson for String {
+        fn from_json(json: &Value) -> Result<String, String> {
+            json.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| "must be a string.".to_string())
+        }
+    }
+
+    impl FromJson for i64 {
+        fn from_json(json: &Value) -> Result<i64, String> {
+            json.as_i64()
+                .ok_or_else(|| "must be a whole number.".to_string())
+        }
+    }
+
+    impl FromJson for u64 {
+        fn from_json(json: &Value) -> Result<u64, String> {
+            json.as_u64()
+                .ok_or_else(|| "must be a whole number that isn't negative.".to_string())
+        }
+    }
+
+    impl FromJson for u32 {
+        fn from_json(json: &Value) -> Result<u32, String> {
+            u64::from_json(json)?
+                .try_into()
+                .map_err(|_| format!("must be at most {}.", u32::MAX))
+        }
+    }
+
+    impl FromJson for usize {
+        fn from_json(json: &Value) -> Result<usize, String> {
+            u64::from_json(json)?
+                .try_into()
+                .map_err(|_| format!("must be at most {}.", usize::MAX))
+        }
+    }
+
+    impl FromJson for Duration {
+        fn from_json(json: &Value) -> Result<Duration, String> {
+            u64::from_json(json).map(Duration::from_millis)
+        }
+    }
+
+    /// Only reached for a field that's set, since `Fields::get` treats `null` as unset.
+    impl<T: FromJson> FromJson for Option<T> {
+        fn from_json(json: &Value) -> Result<Option<T>, String> {
+            T::from_json(json).map(Some)
+        }
+    }
+
+    impl<T: FromJson> FromJson for Vec<T> {
+        fn from_json(json: &Value) -> Result<Vec<T>, String> {
+            json.as_array()
+                .ok_or_else(|| "must be an array.".to_string())?
+                .iter()
+                .enumerate()
+                .map(|(i, value)| T::from_json(value).map_err(|e| format!("[{i}]: {e}")))
+                .collect()
+        }
+    }
+
+    from_json_validated! {
+        UserId => user_id::validate;
+        GroupId => group_id::validate;
+        DocumentId => document_id::validate;
+        GroupName => group_name::validate;
+        DocumentName => document_name::validate;
+        DeviceName => device_name::validate;
+        Category => category::validate;
+        Sensitivity => sensitivity::validate;
+        DataSubject => data_subject::validate;
+    }
+
+    impl FromJson for DeviceId {
+        fn from_json(json: &Value) -> Result<DeviceId, String> {
+            device_id::validate(i64::from_json(json)?)
+        }
+    }
+
+    impl FromJson for PublicKey {
+        fn from_json(json: &Value) -> Result<PublicKey, String> {
+            public_key::validate(u8_conv(&decode_bytes(json)?))
+        }
+    }
+
+    impl FromJson for EncryptedDeks {
+        fn from_json(json: &Value) -> Result<EncryptedDeks, String> {
+            decode_bytes(json).map(EncryptedDeks)
+        }
+    }
+
+    impl FromJson for EncryptedBlindIndexSalt {
+        fn from_json(json: &Value) -> Result<EncryptedBlindIndexSalt, String> {
+            let fields = Fields::new(json, "EncryptedBlindIndexSalt")?;
+            let bytes = |key: &str| -> Result<Vec<u8>, String> {
+                Ok(fields
+                    .get::<EncryptedDeks>(key)?
+                    .map(|e| e.0)
+                    .unwrap_or_default())
+            };
+            Ok(EncryptedBlindIndexSalt {
+                encrypted_deks: bytes("encryptedDeks")?,
+                encrypted_salt_bytes: bytes("encryptedSaltBytes")?,
+            })
+        }
+    }
+
+    impl FromJson for PolicyGrant {
+        fn from_json(json: &Value) -> Result<PolicyGrant, String> {
+            let fields = Fields::new(json, "PolicyGrant")?;
+            Ok(policy_grant::create(
+                fields.get("category")?.as_ref(),
+                fields.get("sensitivity")?.as_ref(),
+                fields.get("dataSubject")?.as_ref(),
+                fields.get("substituteId")?.as_ref(),
+            ))
+        }
+    }
+
+    from_json_fields! {
+        DocumentEncryptOpts from DocumentEncryptOptsBuilder {
+            id: "id",
+            name: "name",
+            grant_to_author: "grantToAuthor",
+            user_grants: "userGrants",
+            group_grants: "groupGrants",
+            policy_grant: "policyGrant",
+        }
+        GroupCreateOpts from GroupCreateOptsBuilder {
+            id: "id",
+            name: "name",
+            add_as_admin: "addAsAdmin",
+            add_as_member: "addAsMember",
+            owner: "owner",
+            admins: "admins",
+            members: "members",
+            needs_rotation: "needsRotation",
+        }
+        UserCreateOpts from UserCreateOptsBuilder {
+            needs_rotation: "needsRotation",
+        }
+        DeviceCreateOpts from DeviceCreateOptsBuilder {
+            name: "name",
+        }
+        DocumentListFilter {
+            include_owner: "includeOwner",
+            include_from_user: "includeFromUser",
+            include_from_group: "includeFromGroup",
+            created_after: "createdAfter",
+            created_before: "createdBefore",
+            updated_after: "updatedAfter",
+            updated_before: "updatedBefore",
+            name_prefix: "namePrefix",
+        }
+        GroupListFilter {
+            admin_only: "adminOnly",
+            member_only: "memberOnly",
+            needs_rotation_only: "needsRotationOnly",
+            name: "name",
+        }
+        PolicyCachingConfig {
+            max_entries: "maxEntries",
+        }
+        RetryPolicy {
+            max_attempts: "maxAttempts",
+            initial_backoff: "initialBackoffMillis",
+            max_backoff: "maxBackoffMillis",
+            retry_server_errors: "retryServerErrors",
+            retry_connection_errors: "retryConnectionErrors",
+            retry_timeouts: "retryTimeouts",
+            idempotent_only: "idempotentOnly",
+        }
+    }
 
-    rustls_platform_verifier::android::init_with_env(&mut env, context.into())
-        .expect("Failed to initialize rustls-platform-verifier for Android");
+    impl FromJson for IronOxideConfig {
+        fn from_json(json: &Value) -> Result<IronOxideConfig, String> {
+            let fields = Fields::new(json, "IronOxideConfig")?;
+            Ok(ironoxide_config::create_with_retry(
+                &fields.get("policyCaching")?.unwrap_or_default(),
+                fields.get("sdkOperationTimeoutMillis")?.as_ref(),
+                &fields.get("retryPolicy")?.unwrap_or_default(),
+            ))
+        }
+    }
 }
 
-pub fn hash<T: Hash>(t: &T) -> i32 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish() as i32
+pub fn to_json<T: json::ToJson>(t: &T) -> String {
+    t.to_json().to_string()
 }
 
-pub fn eq<T: PartialEq>(t: &T, other: &T) -> bool {
-    t.eq(other)
+/// The `fromJson` factory of each class that can be read back from its `toJson()`.
+mod from_json {
+    use super::*;
+    use json::from_str;
+
+    pub fn user_id(json: &str) -> Result<UserId, String> {
+        from_str(json)
+    }
+    pub fn group_id(json: &str) -> Result<GroupId, String> {
+        from_str(json)
+    }
+    pub fn document_id(json: &str) -> Result<DocumentId, String> {
+        from_str(json)
+    }
+    pub fn device_id(json: &str) -> Result<DeviceId, String> {
+        from_str(json)
+    }
+    pub fn group_name(json: &str) -> Result<GroupName, String> {
+        from_str(json)
+    }
+    pub fn document_name(json: &str) -> Result<DocumentName, String> {
+        from_str(json)
+    }
+    pub fn device_name(json: &str) -> Result<DeviceName, String> {
+        from_str(json)
+    }
+    pub fn category(json: &str) -> Result<Category, String> {
+        from_str(json)
+    }
+    pub fn sensitivity(json: &str) -> Result<Sensitivity, String> {
+        from_str(json)
+    }
+    pub fn data_subject(json: &str) -> Result<DataSubject, String> {
+        from_str(json)
+    }
+    pub fn public_key(json: &str) -> Result<PublicKey, String> {
+        from_str(json)
+    }
+    pub fn encrypted_deks(json: &str) -> Result<EncryptedDeks, String> {
+        from_str(json)
+    }
+    pub fn encrypted_blind_index_salt(json: &str) -> Result<EncryptedBlindIndexSalt, String> {
+        from_str(json)
+    }
+    pub fn policy_grant(json: &str) -> Result<PolicyGrant, String> {
+        from_str(json)
+    }
+    pub fn document_encrypt_opts(json: &str) -> Result<DocumentEncryptOpts, String> {
+        from_str(json)
+    }
+    pub fn group_create_opts(json: &str) -> Result<GroupCreateOpts, String> {
+        from_str(json)
+    }
+    pub fn user_create_opts(json: &str) -> Result<UserCreateOpts, String> {
+        from_str(json)
+    }
+    pub fn device_create_opts(json: &str) -> Result<DeviceCreateOpts, String> {
+        from_str(json)
+    }
+    pub fn document_list_filter(json: &str) -> Result<DocumentListFilter, String> {
+        from_str(json)
+    }
+    pub fn group_list_filter(json: &str) -> Result<GroupListFilter, String> {
+        from_str(json)
+    }
+    pub fn policy_caching_config(json: &str) -> Result<PolicyCachingConfig, String> {
+        from_str(json)
+    }
+    pub fn retry_policy(json: &str) -> Result<RetryPolicy, String> {
+        from_str(json)
+    }
+    pub fn iron_oxide_config(json: &str) -> Result<IronOxideConfig, String> {
+        from_str(json)
+    }
+}
+
+/// The compact binary form behind `Serializable` on the JVM and `Parcelable` on Android. It's a
+/// version byte followed by the postcard encoding of the value's fields. The version lets a later
+/// release change the layout and still read what this one wrote. Decoding validates every field
+/// again.
+///
+/// Only types that can be rebuilt from their fields have one. That includes the results built by
+/// the bindings, but not those holding IronOxide types, which can only be made by IronOxide.
+#[cfg(any(feature = "java", feature = "android"))]
+mod binary {
+    use super::*;
+
+    const VERSION: u8 = 1;
+
+    type PolicyGrantFields = (
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+
+    pub trait Binary: Sized {
+        fn encode(&self) -> Result<Vec<u8>, String>;
+        fn decode(bytes: &[u8]) -> Result<Self, String>;
+    }
+
+    fn encoded(result: postcard::Result<Vec<u8>>) -> Result<Vec<u8>, String> {
+        result.map_err(|e| format!("Failed to write the binary form: {e}"))
+    }
+
+    /// The encoded fields, after checking they were written in a version this release can read.
+    fn fields(bytes: &[u8]) -> Result<&[u8], String> {
+        match bytes.split_first() {
+            Some((&VERSION, fields)) => Ok(fields),
+            Some((version, _)) => Err(format!(
+                "Binary format version {version} isn't supported. It may have been written by a newer release."
+            )),
+            None => Err("The binary form is empty.".to_string()),
+        }
+    }
+
+    fn invalid(e: postcard::Error) -> String {
+        format!("Failed to read the binary form: {e}")
+    }
+
+    fn parse_all<T>(
+        values: &[String],
+        parse: fn(&str) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        values.iter().map(|v| parse(v)).collect()
+    }
+
+    fn parse_opt<T>(
+        value: Option<String>,
+        parse: fn(&str) -> Result<T, String>,
+    ) -> Result<Option<T>, String> {
+        value.map(|v| parse(&v)).transpose()
+    }
+
+    fn policy_grant_fields(p: &PolicyGrant) -> PolicyGrantFields {
+        (
+            policy_grant::category(p).map(|c| category::value(&c)),
+            policy_grant::sensitivity(p).map(|s| sensitivity::value(&s)),
+            policy_grant::data_subject(p).map(|d| data_subject::value(&d)),
+            policy_grant::substitute_id(p).map(|u| u.id().to_string()),
+        )
+    }
+
+    fn policy_grant_from_fields(
+        (category, sensitivity, data_subject, substitute_id): PolicyGrantFields,
+    ) -> Result<PolicyGrant, String> {
+        Ok(policy_grant::create(
+            parse_opt(category, category::validate)?.as_ref(),
+            parse_opt(sensitivity, sensitivity::validate)?.as_ref(),
+            parse_opt(data_subject, data_subject::validate)?.as_ref(),
+            parse_opt(substitute_id, user_id::validate)?.as_ref(),
+        ))
+    }
+
+    /// Types that are encoded as the string their `validate` function checks.
+    macro_rules! binary_as_string {
+        ($($t:ty => $value:expr, $validate:path;)*) => {
+            $(impl Binary for $t {
+                fn encode(&self) -> Result<Vec<u8>, String> {
+                    let value: String = $value(self);
+                    encoded(postcard::to_extend(&value, vec![VERSION]))
+                }
+                fn decode(bytes: &[u8]) -> Result<$t, String> {
+                    let value: String = postcard::from_bytes(fields(bytes)?).map_err(invalid)?;
+                    $validate(&value)
+                }
+            })*
+        };
+    }
+
+    binary_as_string! {
+        UserId => |u: &UserId| u.id().to_string(), user_id::validate;
+        GroupId => |g: &GroupId| g.id().to_string(), group_id::validate;
+        DocumentId => |d: &DocumentId| d.id().to_string(), document_id::validate;
+        GroupName => |n: &GroupName| n.name().to_string(), group_name::validate;
+        DocumentName => |n: &DocumentName| n.name().to_string(), document_name::validate;
+        DeviceName => |n: &DeviceName| n.name().to_string(), device_name::validate;
+        Category => category::value, category::validate;
+        Sensitivity => sensitivity::value, sensitivity::validate;
+        DataSubject => data_subject::value, data_subject::validate;
+    }
+
+    impl Binary for DeviceId {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            encoded(postcard::to_extend(&device_id::id(self), vec![VERSION]))
+        }
+        fn decode(bytes: &[u8]) -> Result<DeviceId, String> {
+            device_id::validate(postcard::from_bytes(fields(bytes)?).map_err(invalid)?)
+        }
+    }
+
+    impl Binary for PublicKey {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            let key = i8_conv(&public_key::as_bytes(self)).to_vec();
+            encoded(postcard::to_extend(&key, vec![VERSION]))
+        }
+        fn decode(bytes: &[u8]) -> Result<PublicKey, String> {
+            let key: Vec<u8> = postcard::from_bytes(fields(bytes)?).map_err(invalid)?;
+            public_key::validate(u8_conv(&key))
+        }
+    }
+
+    impl Binary for EncryptedDeks {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            encoded(postcard::to_extend(&self.0, vec![VERSION]))
+        }
+        fn decode(bytes: &[u8]) -> Result<EncryptedDeks, String> {
+            postcard::from_bytes(fields(bytes)?)
+                .map(EncryptedDeks)
+                .map_err(invalid)
+        }
+    }
+
+    impl Binary for PolicyGrant {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            encoded(postcard::to_extend(
+                &policy_grant_fields(self),
+                vec![VERSION],
+            ))
+        }
+        fn decode(bytes: &[u8]) -> Result<PolicyGrant, String> {
+            policy_grant_from_fields(postcard::from_bytes(fields(bytes)?).map_err(invalid)?)
+        }
+    }
+
+    type DocumentEncryptOptsFields = (
+        Option<String>,
+        Option<String>,
+        bool,
+        Vec<String>,
+        Vec<String>,
+        Option<PolicyGrantFields>,
+    );
+
+    impl Binary for DocumentEncryptOpts {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            let s = &self.settings;
+            let fields: DocumentEncryptOptsFields = (
+                s.id.as_ref().map(|id| id.id().to_string()),
+                s.name.as_ref().map(|name| name.name().to_string()),
+                s.grant_to_author,
+                s.user_grants.iter().map(|u| u.id().to_string()).collect(),
+                s.group_grants.iter().map(|g| g.id().to_string()).collect(),
+                s.policy_grant.as_ref().map(policy_grant_fields),
+            );
+            encoded(postcard::to_extend(&fields, vec![VERSION]))
+        }
+        fn decode(bytes: &[u8]) -> Result<DocumentEncryptOpts, String> {
+            let decoded: DocumentEncryptOptsFields =
+                postcard::from_bytes(fields(bytes)?).map_err(invalid)?;
+            let (id, name, grant_to_author, user_grants, group_grants, policy_grant) = decoded;
+            Ok(DocumentEncryptOptsBuilder {
+                id: parse_opt(id, document_id::validate)?,
+                name: parse_opt(name, document_name::validate)?,
+                grant_to_author,
+                user_grants: parse_all(&user_grants, user_id::validate)?,
+                group_grants: parse_all(&group_grants, group_id::validate)?,
+                policy_grant: policy_grant.map(policy_grant_from_fields).transpose()?,
+            }
+            .into())
+        }
+    }
+
+    type GroupCreateOptsFields = (
+        Option<String>,
+        Option<String>,
+        bool,
+        bool,
+        Option<String>,
+        Vec<String>,
+        Vec<String>,
+        bool,
+    );
+
+    impl Binary for GroupCreateOpts {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            let s = &self.settings;
+            let fields: GroupCreateOptsFields = (
+                s.id.as_ref().map(|id| id.id().to_string()),
+                s.name.as_ref().map(|name| name.name().to_string()),
+                s.add_as_admin,
+                s.add_as_member,
+                s.owner.as_ref().map(|u| u.id().to_string()),
+                s.admins.iter().map(|u| u.id().to_string()).collect(),
+                s.members.iter().map(|u| u.id().to_string()).collect(),
+                s.needs_rotation,
+            );
+            encoded(postcard::to_extend(&fields, vec![VERSION]))
+        }
+        fn decode(bytes: &[u8]) -> Result<GroupCreateOpts, String> {
+            let decoded: GroupCreateOptsFields =
+                postcard::from_bytes(fields(bytes)?).map_err(invalid)?;
+            let (id, name, add_as_admin, add_as_member, owner, admins, members, needs_rotation) =
+                decoded;
+            Ok(GroupCreateOptsBuilder {
+                id: parse_opt(id, group_id::validate)?,
+                name: parse_opt(name, group_name::validate)?,
+                add_as_admin,
+                add_as_member,
+                owner: parse_opt(owner, user_id::validate)?,
+                admins: parse_all(&admins, user_id::validate)?,
+                members: parse_all(&members, user_id::validate)?,
+                needs_rotation,
+            }
+            .into())
+        }
+    }
+
+    impl Binary for UserCreateOpts {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            encoded(postcard::to_extend(
+                &self.settings.needs_rotation,
+                vec![VERSION],
+            ))
+        }
+        fn decode(bytes: &[u8]) -> Result<UserCreateOpts, String> {
+            let needs_rotation = postcard::from_bytes(fields(bytes)?).map_err(invalid)?;
+            Ok(UserCreateOptsBuilder { needs_rotation }.into())
+        }
+    }
+
+    impl Binary for DeviceCreateOpts {
+        fn encode(&self) -> Result<Vec<u8>, String> {
+            let name = self.settings.name.as_ref().map(|n| n.name().to_string());
+            encoded(postcard::to_extend(&name, vec![VERSION]))
+        }
+        fn decode(bytes: &[u8]) -> Result<DeviceCreateOpts, String> {
+            let name: Option<String> = postcard::from_bytes(fields(bytes)?).map_err(invalid)?;
+            Ok(DeviceCreateOptsBuilder {
+                name: parse_opt(name, device_name::validate)?,
+            }
+            .into())
+        }
+    }
+
+    /// Results built by the bindings rather than by IronOxide, encoded as a tuple of their fields.
+    macro_rules! binary_as_fields {
+        ($($t:ty: $fields:ty => $to_fields:path, $from_fields:path;)*) => {
+            $(impl Binary for $t {
+                fn encode(&self) -> Result<Vec<u8>, String> {
+                    let fields: $fields = $to_fields(self);
+                    encoded(postcard::to_extend(&fields, vec![VERSION]))
+                }
+                fn decode(bytes: &[u8]) -> Result<$t, String> {
+                    $from_fields(postcard::from_bytes(fields(bytes)?).map_err(invalid)?)
+                }
+            })*
+        };
+    }
+
+    binary_as_fields! {
+        DocumentHeader: DocumentHeaderFields => document_header_fields, document_header_from_fields;
+        EdekRecipient: EdekRecipientFields => edek_recipient_fields, edek_recipient_from_fields;
+        EdekInfo: EdekInfoFields => edek_info_fields, edek_info_from_fields;
+        AccessReportUser: AccessReportUserFields =>
+            access_report_user_fields, access_report_user_from_fields;
+        AccessReportGroup: AccessReportGroupFields =>
+            access_report_group_fields, access_report_group_from_fields;
+        AccessReport: AccessReportFields => access_report_fields, access_report_from_fields;
+        OffboardChange: OffboardChangeFields => offboard_change_fields, offboard_change_from_fields;
+        OffboardReport: OffboardReportFields => offboard_report_fields, offboard_report_from_fields;
+        GroupSyncChangeResult: GroupSyncChangeResultFields =>
+            group_sync_change_result_fields, group_sync_change_result_from_fields;
+        GroupSyncResult: GroupSyncResultFields =>
+            group_sync_result_fields, group_sync_result_from_fields;
+    }
+
+    type DocumentHeaderFields = (u8, String, usize, usize);
+
+    fn document_header_fields(h: &DocumentHeader) -> DocumentHeaderFields {
+        (h.version, h.id.id().to_string(), h.segment_id, h.length)
+    }
+
+    fn document_header_from_fields(
+        (version, id, segment_id, length): DocumentHeaderFields,
+    ) -> Result<DocumentHeader, String> {
+        Ok(DocumentHeader {
+            version,
+            id: document_id::validate(&id)?,
+            segment_id,
+            length,
+        })
+    }
+
+    type EdekRecipientFields = (String, bool, Option<Vec<u8>>);
+
+    fn edek_recipient_fields(r: &EdekRecipient) -> EdekRecipientFields {
+        (
+            r.id.id(),
+            r.id.is_user(),
+            r.public_key
+                .as_ref()
+                .map(|key| i8_conv(&public_key::as_bytes(key)).to_vec()),
+        )
+    }
+
+    fn edek_recipient_from_fields(
+        (id, is_user, public_key): EdekRecipientFields,
+    ) -> Result<EdekRecipient, String> {
+        Ok(EdekRecipient {
+            id: document_decrypt_unmanaged_result::UserOrGroupId::new(id, is_user),
+            public_key: public_key
+                .map(|key| public_key::validate(u8_conv(&key)))
+                .transpose()?,
+        })
+    }
+
+    type EdekInfoFields = (String, usize, Vec<EdekRecipientFields>);
+
+    fn edek_info_fields(e: &EdekInfo) -> EdekInfoFields {
+        (
+            e.id.id().to_string(),
+            e.segment_id,
+            e.recipients.iter().map(edek_recipient_fields).collect(),
+        )
+    }
+
+    fn edek_info_from_fields(
+        (id, segment_id, recipients): EdekInfoFields,
+    ) -> Result<EdekInfo, String> {
+        Ok(EdekInfo {
+            id: document_id::validate(&id)?,
+            segment_id,
+            recipients: recipients
+                .into_iter()
+                .map(edek_recipient_from_fields)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    type AccessReportUserFields = (String, bool, Vec<String>);
+
+    fn access_report_user_fields(u: &AccessReportUser) -> AccessReportUserFields {
+        (
+            u.id.id().to_string(),
+            u.direct,
+            u.via_groups.iter().map(|g| g.id().to_string()).collect(),
+        )
+    }
+
+    fn access_report_user_from_fields(
+        (id, direct, via_groups): AccessReportUserFields,
+    ) -> Result<AccessReportUser, String> {
+        Ok(AccessReportUser {
+            id: user_id::validate(&id)?,
+            direct,
+            via_groups: parse_all(&via_groups, group_id::validate)?,
+        })
+    }
+
+    type AccessReportGroupFields = (String, Option<String>, bool, Option<String>);
+
+    fn access_report_group_fields(g: &AccessReportGroup) -> AccessReportGroupFields {
+        (
+            g.id.id().to_string(),
+            g.name.as_ref().map(|n| n.name().to_string()),
+            g.members_visible,
+            g.error.clone(),
+        )
+    }
+
+    fn access_report_group_from_fields(
+        (id, name, members_visible, error): AccessReportGroupFields,
+    ) -> Result<AccessReportGroup, String> {
+        Ok(AccessReportGroup {
+            id: group_id::validate(&id)?,
+            name: parse_opt(name, group_name::validate)?,
+            members_visible,
+            error,
+        })
+    }
+
+    type AccessReportFields = (
+        String,
+        Vec<AccessReportUserFields>,
+        Vec<AccessReportGroupFields>,
+    );
+
+    fn access_report_fields(r: &AccessReport) -> AccessReportFields {
+        (
+            r.document_id.id().to_string(),
+            r.users.iter().map(access_report_user_fields).collect(),
+            r.groups.iter().map(access_report_group_fields).collect(),
+        )
+    }
+
+    fn access_report_from_fields(
+        (document_id, users, groups): AccessReportFields,
+    ) -> Result<AccessReport, String> {
+        Ok(AccessReport {
+            document_id: document_id::validate(&document_id)?,
+            users: users
+                .into_iter()
+                .map(access_report_user_from_fields)
+                .collect::<Result<_, _>>()?,
+            groups: groups
+                .into_iter()
+                .map(access_report_group_from_fields)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// The step as its `OffboardAction` and the ID of the group or document it changes.
+    type OffboardChangeFields = (u8, String, Option<String>);
+
+    fn offboard_change_fields(c: &OffboardChange) -> OffboardChangeFields {
+        let (action, id) = match &c.step {
+            OffboardStep::RemoveGroupMember(g) => (0, g.id().to_string()),
+            OffboardStep::RemoveGroupAdmin(g) => (1, g.id().to_string()),
+            OffboardStep::RevokeDocumentAccess(d) => (2, d.id().to_string()),
+        };
+        (action, id, c.error.clone())
+    }
+
+    fn offboard_change_from_fields(
+        (action, id, error): OffboardChangeFields,
+    ) -> Result<OffboardChange, String> {
+        let step = match action {
+            0 => OffboardStep::RemoveGroupMember(group_id::validate(&id)?),
+            1 => OffboardStep::RemoveGroupAdmin(group_id::validate(&id)?),
+            2 => OffboardStep::RevokeDocumentAccess(document_id::validate(&id)?),
+            _ => return Err(format!("Unknown offboard action {action}.")),
+        };
+        Ok(OffboardChange { step, error })
+    }
+
+    type OffboardReportFields = (String, bool, Vec<OffboardChangeFields>, Vec<String>);
+
+    fn offboard_report_fields(r: &OffboardReport) -> OffboardReportFields {
+        (
+            r.user.id().to_string(),
+            r.dry_run,
+            r.changes.iter().map(offboard_change_fields).collect(),
+            r.lookup_errors.clone(),
+        )
+    }
+
+    fn offboard_report_from_fields(
+        (user, dry_run, changes, lookup_errors): OffboardReportFields,
+    ) -> Result<OffboardReport, String> {
+        Ok(OffboardReport {
+            user: user_id::validate(&user)?,
+            dry_run,
+            changes: changes
+                .into_iter()
+                .map(offboard_change_from_fields)
+                .collect::<Result<_, _>>()?,
+            lookup_errors,
+        })
+    }
+
+    type GroupSyncChangeResultFields = (String, u8, Option<String>);
+
+    fn group_sync_change_result_fields(r: &GroupSyncChangeResult) -> GroupSyncChangeResultFields {
+        let change = match r.change {
+            GroupSyncChange::AddAdmin => 0,
+            GroupSyncChange::RemoveAdmin => 1,
+            GroupSyncChange::AddMember => 2,
+            GroupSyncChange::RemoveMember => 3,
+        };
+        (r.user.id().to_string(), change, r.error.clone())
+    }
+
+    fn group_sync_change_result_from_fields(
+        (user, change, error): GroupSyncChangeResultFields,
+    ) -> Result<GroupSyncChangeResult, String> {
+        let change = match change {
+            0 => GroupSyncChange::AddAdmin,
+            1 => GroupSyncChange::RemoveAdmin,
+            2 => GroupSyncChange::AddMember,
+            3 => GroupSyncChange::RemoveMember,
+            _ => return Err(format!("Unknown group sync change {change}.")),
+        };
+        Ok(GroupSyncChangeResult {
+            user: user_id::validate(&user)?,
+            change,
+            error,
+        })
+    }
+
+    type GroupSyncResultFields = (bool, Vec<GroupSyncChangeResultFields>);
+
+    fn group_sync_result_fields(r: &GroupSyncResult) -> GroupSyncResultFields {
+        (
+            r.dry_run,
+            r.changes
+                .iter()
+                .map(group_sync_change_result_fields)
+                .collect(),
+        )
+    }
+
+    fn group_sync_result_from_fields(
+        (dry_run, changes): GroupSyncResultFields,
+    ) -> Result<GroupSyncResult, String> {
+        Ok(GroupSyncResult {
+            dry_run,
+            changes: changes
+                .into_iter()
+                .map(group_sync_change_result_from_fields)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn decode_user_id(bytes: &[i8]) -> Result<UserId, String> {
+        UserId::decode(i8_conv(bytes))
+    }
+    pub fn decode_group_id(bytes: &[i8]) -> Result<GroupId, String> {
+        GroupId::decode(i8_conv(bytes))
+    }
+    pub fn decode_document_id(bytes: &[i8]) -> Result<DocumentId, String> {
+        DocumentId::decode(i8_conv(bytes))
+    }
+    pub fn decode_device_id(bytes: &[i8]) -> Result<DeviceId, String> {
+        DeviceId::decode(i8_conv(bytes))
+    }
+    pub fn decode_group_name(bytes: &[i8]) -> Result<GroupName, String> {
+        GroupName::decode(i8_conv(bytes))
+    }
+    pub fn decode_document_name(bytes: &[i8]) -> Result<DocumentName, String> {
+        DocumentName::decode(i8_conv(bytes))
+    }
+    pub fn decode_device_name(bytes: &[i8]) -> Result<DeviceName, String> {
+        DeviceName::decode(i8_conv(bytes))
+    }
+    pub fn decode_category(bytes: &[i8]) -> Result<Category, String> {
+        Category::decode(i8_conv(bytes))
+    }
+    pub fn decode_sensitivity(bytes: &[i8]) -> Result<Sensitivity, String> {
+        Sensitivity::decode(i8_conv(bytes))
+    }
+    pub fn decode_data_subject(bytes: &[i8]) -> Result<DataSubject, String> {
+        DataSubject::decode(i8_conv(bytes))
+    }
+    pub fn decode_public_key(bytes: &[i8]) -> Result<PublicKey, String> {
+        PublicKey::decode(i8_conv(bytes))
+    }
+    pub fn decode_encrypted_deks(bytes: &[i8]) -> Result<EncryptedDeks, String> {
+        EncryptedDeks::decode(i8_conv(bytes))
+    }
+    pub fn decode_policy_grant(bytes: &[i8]) -> Result<PolicyGrant, String> {
+        PolicyGrant::decode(i8_conv(bytes))
+    }
+    pub fn decode_document_encrypt_opts(bytes: &[i8]) -> Result<DocumentEncryptOpts, String> {
+        DocumentEncryptOpts::decode(i8_conv(bytes))
+    }
+    pub fn decode_group_create_opts(bytes: &[i8]) -> Result<GroupCreateOpts, String> {
+        GroupCreateOpts::decode(i8_conv(bytes))
+    }
+    pub fn decode_user_create_opts(bytes: &[i8]) -> Result<UserCreateOpts, String> {
+        UserCreateOpts::decode(i8_conv(bytes))
+    }
+    pub fn decode_device_create_opts(bytes: &[i8]) -> Result<DeviceCreateOpts, String> {
+        DeviceCreateOpts::decode(i8_conv(bytes))
+    }
+    pub fn decode_document_header(bytes: &[i8]) -> Result<DocumentHeader, String> {
+        DocumentHeader::decode(i8_conv(bytes))
+    }
+    pub fn decode_edek_recipient(bytes: &[i8]) -> Result<EdekRecipient, String> {
+        EdekRecipient::decode(i8_conv(bytes))
+    }
+    pub fn decode_edek_info(bytes: &[i8]) -> Result<EdekInfo, String> {
+        EdekInfo::decode(i8_conv(bytes))
+    }
+    pub fn decode_access_report_user(bytes: &[i8]) -> Result<AccessReportUser, String> {
+        AccessReportUser::decode(i8_conv(bytes))
+    }
+    pub fn decode_access_report_group(bytes: &[i8]) -> Result<AccessReportGroup, String> {
+        AccessReportGroup::decode(i8_conv(bytes))
+    }
+    pub fn decode_access_report(bytes: &[i8]) -> Result<AccessReport, String> {
+        AccessReport::decode(i8_conv(bytes))
+    }
+    pub fn decode_offboard_change(bytes: &[i8]) -> Result<OffboardChange, String> {
+        OffboardChange::decode(i8_conv(bytes))
+    }
+    pub fn decode_offboard_report(bytes: &[i8]) -> Result<OffboardReport, String> {
+        OffboardReport::decode(i8_conv(bytes))
+    }
+    pub fn decode_group_sync_change_result(bytes: &[i8]) -> Result<GroupSyncChangeResult, String> {
+        GroupSyncChangeResult::decode(i8_conv(bytes))
+    }
+    pub fn decode_group_sync_result(bytes: &[i8]) -> Result<GroupSyncResult, String> {
+        GroupSyncResult::decode(i8_conv(bytes))
+    }
+}
+
+#[cfg(any(feature = "java", feature = "android"))]
+pub fn encode_binary<T: binary::Binary>(t: &T) -> Result<Vec<i8>, Error> {
+    Ok(u8_conv(&t.encode().map_err(Error::other)?).to_vec())
 }
 
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq)]
 pub struct UserWithKey((UserId, PublicKey));
 impl UserWithKey {
     pub fn user(&self) -> UserId {
@@ -65,7 +2328,7 @@ impl UserWithKey {
 }
 
 // This was created because Option<bool> cannot be converted to Java
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq)]
 pub struct NullableBoolean(bool);
 impl NullableBoolean {
     pub fn boolean(&self) -> bool {
@@ -143,6 +2406,72 @@ mod document_id {
     }
 }
 
+/// Parsed header of an encrypted document, read without an SDK instance.
+#[derive(Debug)]
+pub struct DocumentHeader {
+    version: u8,
+    id: DocumentId,
+    segment_id: usize,
+    length: usize,
+}
+
+mod document_header {
+    use super::*;
+    const SUPPORTED_VERSION: u8 = 2;
+    const PREFIX_LENGTH: usize = 3;
+
+    pub fn parse(encrypted_document: &[i8]) -> Result<DocumentHeader, String> {
+        let bytes = i8_conv(encrypted_document);
+        let prefix = bytes.get(..PREFIX_LENGTH).ok_or_else(|| {
+            format!(
+                "Data is too short to be an encrypted document. Expected at least {PREFIX_LENGTH} bytes, got {}.",
+                bytes.len()
+            )
+        })?;
+        let version = prefix[0];
+        if version != SUPPORTED_VERSION {
+            return Err(format!(
+                "Document header version {version} isn't supported. The data may not be an IronCore encrypted document."
+            ));
+        }
+        let length = PREFIX_LENGTH + u16::from_be_bytes([prefix[1], prefix[2]]) as usize;
+        let json = bytes.get(PREFIX_LENGTH..length).ok_or_else(|| {
+            format!(
+                "Encrypted document is truncated. The header is {length} bytes, but only {} bytes were provided.",
+                bytes.len()
+            )
+        })?;
+        let header: serde_json::Value = serde_json::from_slice(json)
+            .map_err(|e| format!("Document header is corrupted and couldn't be parsed: {e}"))?;
+        let id = header
+            .get("_did_")
+            .and_then(|id| id.as_str())
+            .ok_or("Document header is corrupted: it doesn't contain a document ID.")?;
+        let segment_id = header
+            .get("_sid_")
+            .and_then(|id| id.as_u64())
+            .ok_or("Document header is corrupted: it doesn't contain a segment ID.")?;
+        Ok(DocumentHeader {
+            version,
+            id: document_id::validate(id)?,
+            segment_id: segment_id as usize,
+            length,
+        })
+    }
+    pub fn version(h: &DocumentHeader) -> u8 {
+        h.version
+    }
+    pub fn id(h: &DocumentHeader) -> DocumentId {
+        h.id.clone()
+    }
+    pub fn segment_id(h: &DocumentHeader) -> usize {
+        h.segment_id
+    }
+    pub fn length(h: &DocumentHeader) -> usize {
+        h.length
+    }
+}
+
 mod document_name {
     use super::*;
     use std::convert::TryInto;
@@ -187,6 +2516,13 @@ mod public_key {
     pub fn as_bytes(pk: &PublicKey) -> Vec<i8> {
         u8_conv(&pk.as_bytes()).to_vec()
     }
+    /// Lowercase hex SHA-256 of the key bytes.
+    pub fn fingerprint(pk: &PublicKey) -> String {
+        Sha256::digest(pk.as_bytes())
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
 }
 
 mod private_key {
@@ -211,17 +2547,107 @@ mod device_signing_keys {
     }
 }
 
+/// Settings for a `DeviceCreateOpts`, which can be set one at a time before it's built.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DeviceCreateOptsBuilder {
+    name: Option<DeviceName>,
+}
+
+/// IronOxide's `DeviceCreateOpts` along with the settings it was created from, which IronOxide
+/// doesn't expose.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DeviceCreateOpts {
+    sdk: ironoxide::user::DeviceCreateOpts,
+    settings: DeviceCreateOptsBuilder,
+}
+impl From<DeviceCreateOptsBuilder> for DeviceCreateOpts {
+    fn from(settings: DeviceCreateOptsBuilder) -> DeviceCreateOpts {
+        DeviceCreateOpts {
+            sdk: ironoxide::user::DeviceCreateOpts::new(settings.name.clone()),
+            settings,
+        }
+    }
+}
+impl Deref for DeviceCreateOpts {
+    type Target = ironoxide::user::DeviceCreateOpts;
+    fn deref(&self) -> &ironoxide::user::DeviceCreateOpts {
+        &self.sdk
+    }
+}
+
 mod device_create_opts {
     use super::*;
     pub fn create(name: Option<&DeviceName>) -> DeviceCreateOpts {
-        DeviceCreateOpts::new(name.cloned())
+        DeviceCreateOptsBuilder {
+            name: name.cloned(),
+        }
+        .into()
+    }
+    pub fn name(o: &DeviceCreateOpts) -> Option<DeviceName> {
+        o.settings.name.clone()
+    }
+}
+
+mod device_create_opts_builder {
+    use super::*;
+    pub fn name(_b: &DeviceCreateOptsBuilder, name: &DeviceName) -> DeviceCreateOptsBuilder {
+        DeviceCreateOptsBuilder {
+            name: Some(name.clone()),
+        }
+    }
+    pub fn build(b: &DeviceCreateOptsBuilder) -> DeviceCreateOpts {
+        b.clone().into()
+    }
+}
+
+/// Settings for a `UserCreateOpts`, which can be set one at a time before it's built.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UserCreateOptsBuilder {
+    needs_rotation: bool,
+}
+
+/// IronOxide's `UserCreateOpts` along with the settings it was created from, which IronOxide
+/// doesn't expose.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UserCreateOpts {
+    sdk: ironoxide::user::UserCreateOpts,
+    settings: UserCreateOptsBuilder,
+}
+impl From<UserCreateOptsBuilder> for UserCreateOpts {
+    fn from(settings: UserCreateOptsBuilder) -> UserCreateOpts {
+        UserCreateOpts {
+            sdk: ironoxide::user::UserCreateOpts::new(settings.needs_rotation),
+            settings,
+        }
+    }
+}
+impl Deref for UserCreateOpts {
+    type Target = ironoxide::user::UserCreateOpts;
+    fn deref(&self) -> &ironoxide::user::UserCreateOpts {
+        &self.sdk
+    }
+}
+
+mod user_create_opts {
+    use super::*;
+    pub fn create(needs_rotation: bool) -> UserCreateOpts {
+        UserCreateOptsBuilder { needs_rotation }.into()
+    }
+    pub fn needs_rotation(o: &UserCreateOpts) -> bool {
+        o.settings.needs_rotation
     }
 }
 
-mod user_create_opts {
+mod user_create_opts_builder {
     use super::*;
-    pub fn create(needs_rotation: bool) -> UserCreateOpts {
-        UserCreateOpts::new(needs_rotation)
+    pub fn needs_rotation(
+        _b: &UserCreateOptsBuilder,
+        needs_rotation: bool,
+    ) -> UserCreateOptsBuilder {
+        UserCreateOptsBuilder { needs_rotation }
+    }
+    pub fn build(b: &UserCreateOptsBuilder) -> UserCreateOpts {
+        b.clone().into()
     }
 }
 
@@ -287,36 +2713,186 @@ mod data_subject {
     }
 }
 
-mod document_create_opt {
-    use super::*;
-    use ironoxide::document::{DocumentEncryptOpts, ExplicitGrant};
-    use itertools::EitherOrBoth;
-    pub fn create(
-        id: Option<&DocumentId>,
-        name: Option<&DocumentName>,
-        grant_to_author: bool,
-        user_grants: &[UserId],
-        group_grants: &[GroupId],
-        policy_grant: Option<&PolicyGrant>,
-    ) -> DocumentEncryptOpts {
-        let users_and_groups: Vec<UserOrGroup> = user_grants
+/// Settings for a `DocumentEncryptOpts`, which can be set one at a time before it's built.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentEncryptOptsBuilder {
+    id: Option<DocumentId>,
+    name: Option<DocumentName>,
+    grant_to_author: bool,
+    user_grants: Vec<UserId>,
+    group_grants: Vec<GroupId>,
+    policy_grant: Option<PolicyGrant>,
+}
+impl Default for DocumentEncryptOptsBuilder {
+    /// Only the author is granted access, as with IronOxide's default.
+    fn default() -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            id: None,
+            name: None,
+            grant_to_author: true,
+            user_grants: vec![],
+            group_grants: vec![],
+            policy_grant: None,
+        }
+    }
+}
+
+/// IronOxide's `DocumentEncryptOpts` along with the settings it was created from, which IronOxide
+/// doesn't expose.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentEncryptOpts {
+    sdk: ironoxide::document::DocumentEncryptOpts,
+    settings: DocumentEncryptOptsBuilder,
+}
+impl Default for DocumentEncryptOpts {
+    fn default() -> DocumentEncryptOpts {
+        DocumentEncryptOptsBuilder::default().into()
+    }
+}
+impl From<DocumentEncryptOptsBuilder> for DocumentEncryptOpts {
+    fn from(settings: DocumentEncryptOptsBuilder) -> DocumentEncryptOpts {
+        use ironoxide::document::ExplicitGrant;
+        let users_and_groups: Vec<UserOrGroup> = settings
+            .user_grants
             .iter()
             .cloned()
             .map(|u| UserOrGroup::User { id: u })
             .chain(
-                group_grants
+                settings
+                    .group_grants
                     .iter()
                     .cloned()
                     .map(|g| UserOrGroup::Group { id: g }),
             )
             .collect();
 
-        let explicit = ExplicitGrant::new(grant_to_author, &users_and_groups);
-        let grants = match policy_grant.cloned() {
+        let explicit = ExplicitGrant::new(settings.grant_to_author, &users_and_groups);
+        let grants = match settings.policy_grant.clone() {
             Some(policy) => EitherOrBoth::Both(explicit, policy),
             None => EitherOrBoth::Left(explicit),
         };
-        DocumentEncryptOpts::new(id.cloned(), name.cloned(), grants)
+        DocumentEncryptOpts {
+            sdk: ironoxide::document::DocumentEncryptOpts::new(
+                settings.id.clone(),
+                settings.name.clone(),
+                grants,
+            ),
+            settings,
+        }
+    }
+}
+impl Deref for DocumentEncryptOpts {
+    type Target = ironoxide::document::DocumentEncryptOpts;
+    fn deref(&self) -> &ironoxide::document::DocumentEncryptOpts {
+        &self.sdk
+    }
+}
+
+mod document_create_opt {
+    use super::*;
+    pub fn create(
+        id: Option<&DocumentId>,
+        name: Option<&DocumentName>,
+        grant_to_author: bool,
+        user_grants: &[UserId],
+        group_grants: &[GroupId],
+        policy_grant: Option<&PolicyGrant>,
+    ) -> DocumentEncryptOpts {
+        DocumentEncryptOptsBuilder {
+            id: id.cloned(),
+            name: name.cloned(),
+            grant_to_author,
+            user_grants: user_grants.to_vec(),
+            group_grants: group_grants.to_vec(),
+            policy_grant: policy_grant.cloned(),
+        }
+        .into()
+    }
+    pub fn id(o: &DocumentEncryptOpts) -> Option<DocumentId> {
+        o.settings.id.clone()
+    }
+    pub fn name(o: &DocumentEncryptOpts) -> Option<DocumentName> {
+        o.settings.name.clone()
+    }
+    pub fn grant_to_author(o: &DocumentEncryptOpts) -> bool {
+        o.settings.grant_to_author
+    }
+    pub fn user_grants(o: &DocumentEncryptOpts) -> Vec<UserId> {
+        o.settings.user_grants.clone()
+    }
+    pub fn group_grants(o: &DocumentEncryptOpts) -> Vec<GroupId> {
+        o.settings.group_grants.clone()
+    }
+    pub fn policy_grant(o: &DocumentEncryptOpts) -> Option<PolicyGrant> {
+        o.settings.policy_grant.clone()
+    }
+}
+
+mod document_encrypt_opts_builder {
+    use super::*;
+    pub fn id(b: &DocumentEncryptOptsBuilder, id: &DocumentId) -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            id: Some(id.clone()),
+            ..b.clone()
+        }
+    }
+    pub fn name(b: &DocumentEncryptOptsBuilder, name: &DocumentName) -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            name: Some(name.clone()),
+            ..b.clone()
+        }
+    }
+    pub fn grant_to_author(
+        b: &DocumentEncryptOptsBuilder,
+        grant_to_author: bool,
+    ) -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            grant_to_author,
+            ..b.clone()
+        }
+    }
+    pub fn user_grants(
+        b: &DocumentEncryptOptsBuilder,
+        users: &[UserId],
+    ) -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            user_grants: users.to_vec(),
+            ..b.clone()
+        }
+    }
+    pub fn group_grants(
+        b: &DocumentEncryptOptsBuilder,
+        groups: &[GroupId],
+    ) -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            group_grants: groups.to_vec(),
+            ..b.clone()
+        }
+    }
+    pub fn policy_grant(
+        b: &DocumentEncryptOptsBuilder,
+        policy: &PolicyGrant,
+    ) -> DocumentEncryptOptsBuilder {
+        DocumentEncryptOptsBuilder {
+            policy_grant: Some(policy.clone()),
+            ..b.clone()
+        }
+    }
+    /// Fails if nobody would be able to decrypt the document, rather than leaving that to the
+    /// webservice once the document is encrypted.
+    pub fn build(b: &DocumentEncryptOptsBuilder) -> Result<DocumentEncryptOpts, String> {
+        if !b.grant_to_author
+            && b.user_grants.is_empty()
+            && b.group_grants.is_empty()
+            && b.policy_grant.is_none()
+        {
+            return Err(
+                "DocumentEncryptOpts must grant access to the author, a user, a group \
+                or a policy."
+                    .to_string(),
+            );
+        }
+        Ok(b.clone().into())
     }
 }
 
@@ -436,6 +3012,84 @@ mod user_update_private_key_result {
     }
 }
 
+/// An initialized SDK, along with any private key rotations that were found to be pending
+/// while initializing it.
+pub struct InitAndRotationCheck {
+    sdk: IronOxide,
+    rotation: Option<PrivateKeyRotationCheckResult>,
+}
+impl InitAndRotationCheck {
+    fn new(
+        check: ironoxide::InitAndRotationCheck<BlockingIronOxide>,
+        retry_policy: &RetryPolicy,
+    ) -> InitAndRotationCheck {
+        let (sdk, rotation) = match check {
+            ironoxide::InitAndRotationCheck::NoRotationNeeded(sdk) => (sdk, None),
+            ironoxide::InitAndRotationCheck::RotationNeeded(sdk, rotation) => (sdk, Some(rotation)),
+        };
+        InitAndRotationCheck {
+            sdk: IronOxide::new(sdk, retry_policy),
+            rotation,
+        }
+    }
+}
+
+mod init_and_rotation_check {
+    use super::*;
+    pub fn ironoxide(c: &InitAndRotationCheck) -> IronOxide {
+        c.sdk.clone()
+    }
+    pub fn is_rotation_needed(c: &InitAndRotationCheck) -> bool {
+        c.rotation.is_some()
+    }
+    pub fn rotation(c: &InitAndRotationCheck) -> Option<PrivateKeyRotationCheckResult> {
+        c.rotation.clone()
+    }
+}
+
+mod private_key_rotation_check_result {
+    use super::*;
+    pub fn user_rotation_needed(r: &PrivateKeyRotationCheckResult) -> Option<UserId> {
+        r.user_rotation_needed().cloned()
+    }
+    pub fn group_rotation_needed(r: &PrivateKeyRotationCheckResult) -> Vec<GroupId> {
+        r.group_rotation_needed()
+            .map_or_else(Vec::new, |groups| groups.to_vec())
+    }
+}
+
+/// Keys rotated by `rotate_all`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RotateAllResult {
+    user: Option<UserUpdatePrivateKeyResult>,
+    groups: Vec<GroupUpdatePrivateKeyResult>,
+}
+
+mod rotate_all_result {
+    use super::*;
+    pub fn user(r: &RotateAllResult) -> Option<UserUpdatePrivateKeyResult> {
+        r.user.clone()
+    }
+    pub fn groups(r: &RotateAllResult) -> Vec<GroupUpdatePrivateKeyResult> {
+        r.groups.clone()
+    }
+}
+
+/// Result of changing a user's password. The SDK reuses `UserCreateResult` for it, which can only
+/// be bound once, so it's wrapped here.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UserUpdateResult(ironoxide::user::UserUpdateResult);
+
+mod user_update_result {
+    use super::*;
+    pub fn user_public_key(u: &UserUpdateResult) -> PublicKey {
+        u.0.user_public_key().clone()
+    }
+    pub fn needs_rotation(u: &UserUpdateResult) -> bool {
+        u.0.needs_rotation()
+    }
+}
+
 mod user_device {
     use super::*;
     pub fn id(u: &UserDevice) -> DeviceId {
@@ -455,6 +3109,42 @@ mod user_device {
     }
 }
 
+/// The outcome of deleting one device as part of a bulk device deletion.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DeviceDeleteOutcome {
+    device: UserDevice,
+    error: Option<String>,
+}
+
+/// Every device a bulk device deletion tried to delete.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DeviceDeleteReport(Vec<DeviceDeleteOutcome>);
+
+mod device_delete_report {
+    use super::*;
+    pub fn device(o: &DeviceDeleteOutcome) -> UserDevice {
+        o.device.clone()
+    }
+    pub fn error(o: &DeviceDeleteOutcome) -> Option<String> {
+        o.error.clone()
+    }
+    pub fn is_deleted(o: &DeviceDeleteOutcome) -> bool {
+        o.error.is_none()
+    }
+    pub fn result(r: &DeviceDeleteReport) -> Vec<DeviceDeleteOutcome> {
+        r.0.clone()
+    }
+    pub fn deleted(r: &DeviceDeleteReport) -> Vec<DeviceId> {
+        r.0.iter()
+            .filter(|o| o.error.is_none())
+            .map(|o| o.device.id().clone())
+            .collect()
+    }
+    pub fn failed(r: &DeviceDeleteReport) -> Vec<DeviceDeleteOutcome> {
+        r.0.iter().filter(|o| o.error.is_some()).cloned().collect()
+    }
+}
+
 mod user_device_list_result {
     use super::*;
     pub fn result(u: &UserDeviceListResult) -> Vec<UserDevice> {
@@ -488,6 +3178,162 @@ mod document_list_meta {
     }
 }
 
+/// Which documents a filtered listing returns. Every condition that's set has to match.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentListFilter {
+    include_owner: bool,
+    include_from_user: bool,
+    include_from_group: bool,
+    created_after: Option<i64>,
+    created_before: Option<i64>,
+    updated_after: Option<i64>,
+    updated_before: Option<i64>,
+    name_prefix: Option<String>,
+}
+impl Default for DocumentListFilter {
+    fn default() -> DocumentListFilter {
+        DocumentListFilter {
+            include_owner: true,
+            include_from_user: true,
+            include_from_group: true,
+            created_after: None,
+            created_before: None,
+            updated_after: None,
+            updated_before: None,
+            name_prefix: None,
+        }
+    }
+}
+impl DocumentListFilter {
+    fn matches(&self, d: &DocumentListMeta) -> bool {
+        let in_range = |time: &OffsetDateTime, after: Option<i64>, before: Option<i64>| {
+            let millis = unix_millis(time);
+            after.is_none_or(|after| millis > after) && before.is_none_or(|before| millis < before)
+        };
+        let association_matches = match d.association_type() {
+            AssociationType::Owner => self.include_owner,
+            AssociationType::FromUser => self.include_from_user,
+            AssociationType::FromGroup => self.include_from_group,
+        };
+        let name_matches = self.name_prefix.as_ref().is_none_or(|prefix| {
+            d.name()
+                .is_some_and(|name| name.name().starts_with(prefix.as_str()))
+        });
+        association_matches
+            && name_matches
+            && in_range(d.created(), self.created_after, self.created_before)
+            && in_range(d.last_updated(), self.updated_after, self.updated_before)
+    }
+}
+
+/// Milliseconds since the Unix epoch, the same as the Java `Date`s and C++ timestamps we hand out.
+fn unix_millis(time: &OffsetDateTime) -> i64 {
+    time.unix_timestamp() * 1000 + time.millisecond() as i64
+}
+
+/// The documents matching `filter`, in a stable order so pages don't overlap or skip documents.
+/// IronOxide has no server-side paging or filtering, so this always fetches every document the user
+/// can decrypt.
+fn filtered_documents(
+    sdk: &IronOxide,
+    filter: &DocumentListFilter,
+) -> ironoxide::Result<Vec<DocumentListMeta>> {
+    let mut documents: Vec<DocumentListMeta> = sdk
+        .retry(true, |sdk| sdk.document_list())?
+        .result()
+        .iter()
+        .filter(|d| filter.matches(d))
+        .cloned()
+        .collect();
+    documents.sort_by(|a, b| document_list_key(a).cmp(&document_list_key(b)));
+    Ok(documents)
+}
+
+fn document_list_key(d: &DocumentListMeta) -> (i128, &str) {
+    (d.created().unix_timestamp_nanos(), d.id().id())
+}
+
+mod document_list_filter {
+    use super::*;
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        include_owner: bool,
+        include_from_user: bool,
+        include_from_group: bool,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
+        updated_after: Option<i64>,
+        updated_before: Option<i64>,
+        name_prefix: Option<&str>,
+    ) -> DocumentListFilter {
+        DocumentListFilter {
+            include_owner,
+            include_from_user,
+            include_from_group,
+            created_after,
+            created_before,
+            updated_after,
+            updated_before,
+            name_prefix: name_prefix.map(str::to_string),
+        }
+    }
+}
+
+/// One page of a filtered listing. Every page taken from the same first page shares the sorted
+/// documents fetched for it, so moving to the next page doesn't contact the webservice.
+pub struct DocumentListPage {
+    documents: Arc<[DocumentListMeta]>,
+    start: usize,
+    page_size: usize,
+}
+impl DocumentListPage {
+    fn result(&self) -> &[DocumentListMeta] {
+        &self.documents[self.start..self.end()]
+    }
+    fn end(&self) -> usize {
+        (self.start + self.page_size).min(self.documents.len())
+    }
+    fn has_next_page(&self) -> bool {
+        self.end() < self.documents.len()
+    }
+}
+impl std::fmt::Debug for DocumentListPage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("DocumentListPage")
+            .field("result", &self.result())
+            .field("has_next_page", &self.has_next_page())
+            .finish()
+    }
+}
+
+mod document_list_page {
+    use super::*;
+    pub fn result(p: &DocumentListPage) -> Vec<DocumentListMeta> {
+        p.result().to_vec()
+    }
+    pub fn next_page(p: &DocumentListPage) -> Option<DocumentListPage> {
+        p.has_next_page().then(|| DocumentListPage {
+            documents: p.documents.clone(),
+            start: p.end(),
+            page_size: p.page_size,
+        })
+    }
+}
+
+/// Hands out documents one at a time so the foreign side only holds the ones it's using.
+#[derive(Debug)]
+pub struct DocumentListCursor(std::vec::IntoIter<DocumentListMeta>);
+
+mod document_list_cursor {
+    use super::*;
+    pub fn next(c: &mut DocumentListCursor) -> Option<DocumentListMeta> {
+        c.0.next()
+    }
+    pub fn remaining(c: &DocumentListCursor) -> usize {
+        c.0.len()
+    }
+}
+
 mod document_metadata_result {
     use super::*;
     pub fn id(d: &DocumentMetadataResult) -> DocumentId {
@@ -513,6 +3359,73 @@ mod document_metadata_result {
     }
 }
 
+/// How one user can decrypt a document: directly, through groups, or both.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AccessReportUser {
+    id: UserId,
+    direct: bool,
+    via_groups: Vec<GroupId>,
+}
+
+/// A group a document is shared with. `members_visible` is false if the caller can't see who
+/// is in the group, in which case its members are missing from the report's users. `error` says
+/// why, if the group couldn't be read at all.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AccessReportGroup {
+    id: GroupId,
+    name: Option<GroupName>,
+    members_visible: bool,
+    error: Option<String>,
+}
+
+/// Who can decrypt a document and how, as far as the caller can see.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AccessReport {
+    document_id: DocumentId,
+    users: Vec<AccessReportUser>,
+    groups: Vec<AccessReportGroup>,
+}
+
+mod access_report {
+    use super::*;
+    pub fn user_id(u: &AccessReportUser) -> UserId {
+        u.id.clone()
+    }
+    pub fn has_direct_access(u: &AccessReportUser) -> bool {
+        u.direct
+    }
+    pub fn via_groups(u: &AccessReportUser) -> Vec<GroupId> {
+        u.via_groups.clone()
+    }
+    pub fn has_direct_and_group_access(u: &AccessReportUser) -> bool {
+        u.direct && !u.via_groups.is_empty()
+    }
+    pub fn group_id(g: &AccessReportGroup) -> GroupId {
+        g.id.clone()
+    }
+    pub fn group_name(g: &AccessReportGroup) -> Option<GroupName> {
+        g.name.clone()
+    }
+    pub fn members_visible(g: &AccessReportGroup) -> bool {
+        g.members_visible
+    }
+    pub fn group_error(g: &AccessReportGroup) -> Option<String> {
+        g.error.clone()
+    }
+    pub fn document_id(r: &AccessReport) -> DocumentId {
+        r.document_id.clone()
+    }
+    pub fn users(r: &AccessReport) -> Vec<AccessReportUser> {
+        r.users.clone()
+    }
+    pub fn groups(r: &AccessReport) -> Vec<AccessReportGroup> {
+        r.groups.clone()
+    }
+    pub fn is_complete(r: &AccessReport) -> bool {
+        r.groups.iter().all(|g| g.members_visible)
+    }
+}
+
 mod document_encrypt_result {
     use super::*;
 
@@ -577,7 +3490,7 @@ mod document_decrypt_result {
 mod document_decrypt_unmanaged_result {
     use super::*;
     /// Generic translation of ironoxide's UserOrGroup enum
-    #[derive(Eq, Hash, PartialEq)]
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
     pub struct UserOrGroupId {
         id: String,
         is_user: bool,
@@ -614,6 +3527,177 @@ mod document_decrypt_unmanaged_result {
     }
 }
 
+/// A user or group that an unmanaged document's EDEKs are encrypted to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EdekRecipient {
+    id: document_decrypt_unmanaged_result::UserOrGroupId,
+    public_key: Option<PublicKey>,
+}
+
+/// Contents of an unmanaged document's EDEKs, read without an SDK instance.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct EdekInfo {
+    id: DocumentId,
+    segment_id: usize,
+    recipients: Vec<EdekRecipient>,
+}
+
+mod edek_info {
+    use super::*;
+    use crate::document_decrypt_unmanaged_result::UserOrGroupId;
+
+    /// Value of one protobuf field. Fixed-width values aren't used by EDEKs and are skipped.
+    enum Field<'a> {
+        Varint(u64),
+        Bytes(&'a [u8]),
+        Fixed,
+    }
+
+    /// Reads the fields of a protobuf message. Only as much of the wire format as EDEKs need.
+    struct Fields<'a>(&'a [u8]);
+
+    impl<'a> Fields<'a> {
+        fn varint(&mut self) -> Result<u64, String> {
+            let mut value = 0u64;
+            for shift in (0..64).step_by(7) {
+                let (&byte, rest) = self.0.split_first().ok_or("value is truncated")?;
+                self.0 = rest;
+                value |= u64::from(byte & 0x7f) << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(value);
+                }
+            }
+            Err("varint is too long".to_string())
+        }
+        fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+            if len > self.0.len() {
+                return Err("value is truncated".to_string());
+            }
+            let (value, rest) = self.0.split_at(len);
+            self.0 = rest;
+            Ok(value)
+        }
+        fn next_field(&mut self) -> Result<Option<(u64, Field<'a>)>, String> {
+            if self.0.is_empty() {
+                return Ok(None);
+            }
+            let key = self.varint()?;
+            let value = match key & 0x7 {
+                0 => Field::Varint(self.varint()?),
+                1 => self.take(8).map(|_| Field::Fixed)?,
+                2 => {
+                    let len = self.varint()? as usize;
+                    Field::Bytes(self.take(len)?)
+                }
+                5 => self.take(4).map(|_| Field::Fixed)?,
+                wire_type => return Err(format!("unsupported wire type {wire_type}")),
+            };
+            Ok(Some((key >> 3, value)))
+        }
+    }
+
+    fn utf8(bytes: &[u8]) -> Result<String, String> {
+        String::from_utf8(bytes.to_vec()).map_err(|_| "ID isn't valid UTF-8".to_string())
+    }
+
+    /// Each coordinate is left padded to 32 bytes, matching `PublicKey::as_bytes`.
+    fn parse_public_key(bytes: &[u8]) -> Result<Option<PublicKey>, String> {
+        let mut key = [0u8; 64];
+        let mut fields = Fields(bytes);
+        while let Some((number, field)) = fields.next_field()? {
+            match (number, field) {
+                (1, Field::Bytes(x)) if x.len() <= 32 => key[32 - x.len()..32].copy_from_slice(x),
+                (2, Field::Bytes(y)) if y.len() <= 32 => key[64 - y.len()..].copy_from_slice(y),
+                _ => (),
+            }
+        }
+        Ok(PublicKey::try_from(&key[..]).ok())
+    }
+
+    fn parse_recipient(bytes: &[u8]) -> Result<Option<EdekRecipient>, String> {
+        let mut id = None;
+        let mut key = None;
+        let mut fields = Fields(bytes);
+        while let Some((number, field)) = fields.next_field()? {
+            match (number, field) {
+                (1, Field::Bytes(user)) => id = Some(UserOrGroupId::new(utf8(user)?, true)),
+                (2, Field::Bytes(group)) => id = Some(UserOrGroupId::new(utf8(group)?, false)),
+                (3, Field::Bytes(pk)) => key = parse_public_key(pk)?,
+                _ => (),
+            }
+        }
+        Ok(id.map(|id| EdekRecipient {
+            id,
+            public_key: key,
+        }))
+    }
+
+    fn parse_edek(bytes: &[u8]) -> Result<Option<EdekRecipient>, String> {
+        let mut fields = Fields(bytes);
+        while let Some((number, field)) = fields.next_field()? {
+            if let (1, Field::Bytes(user_or_group)) = (number, field) {
+                return parse_recipient(user_or_group);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn parse(encrypted_deks: &[i8]) -> Result<EdekInfo, String> {
+        let mut id = None;
+        let mut segment_id = 0;
+        let mut recipients = vec![];
+        let mut fields = Fields(i8_conv(encrypted_deks));
+        let corrupted =
+            |e: String| format!("Encrypted DEKs are corrupted or aren't IronCore EDEKs: {e}.");
+        while let Some((number, field)) = fields.next_field().map_err(corrupted)? {
+            match (number, field) {
+                (1, Field::Bytes(e)) => recipients.extend(parse_edek(e).map_err(corrupted)?),
+                (2, Field::Bytes(doc_id)) => id = Some(utf8(doc_id).map_err(corrupted)?),
+                (3, Field::Varint(segment)) => segment_id = segment as usize,
+                _ => (),
+            }
+        }
+        let id = id.ok_or_else(|| corrupted("no document ID".to_string()))?;
+        Ok(EdekInfo {
+            id: document_id::validate(&id)?,
+            segment_id,
+            recipients,
+        })
+    }
+    pub fn id(e: &EdekInfo) -> DocumentId {
+        e.id.clone()
+    }
+    pub fn segment_id(e: &EdekInfo) -> usize {
+        e.segment_id
+    }
+    pub fn recipients(e: &EdekInfo) -> Vec<EdekRecipient> {
+        e.recipients.clone()
+    }
+    pub fn users(e: &EdekInfo) -> Vec<UserId> {
+        e.recipients
+            .iter()
+            .filter(|r| r.id.is_user())
+            .filter_map(|r| user_id::validate(&r.id.id()).ok())
+            .collect()
+    }
+    pub fn groups(e: &EdekInfo) -> Vec<GroupId> {
+        e.recipients
+            .iter()
+            .filter(|r| r.id.is_group())
+            .filter_map(|r| group_id::validate(&r.id.id()).ok())
+            .collect()
+    }
+    pub fn user_or_group(r: &EdekRecipient) -> UserOrGroupId {
+        r.id.clone()
+    }
+    pub fn public_key(r: &EdekRecipient) -> Option<PublicKey> {
+        r.public_key.clone()
+    }
+    pub fn fingerprint(r: &EdekRecipient) -> Option<String> {
+        r.public_key.as_ref().map(public_key::fingerprint)
+    }
+}
+
 mod document_metadata_unmanaged_result {
     use super::*;
     pub fn id(d: &DocumentMetadataUnmanagedResult) -> DocumentId {
@@ -645,20 +3729,126 @@ mod document_access_unmanaged_result {
     }
 }
 
-impl document_access_change_result::DocumentAccessChange for DocumentAccessUnmanagedResult {
+impl document_access_change_result::DocumentAccessChange for DocumentAccessUnmanagedResult {
+    fn changed(&self) -> document_access_change_result::SucceededResult {
+        document_access_change_result::to_succeeded_result(self.succeeded())
+    }
+
+    fn errors(&self) -> document_access_change_result::FailedResult {
+        document_access_change_result::to_failed_result(self.failed())
+    }
+}
+
+/// EDEKs of one unmanaged document, so that several of them can be passed at once.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EncryptedDeks(Vec<u8>);
+
+mod encrypted_deks {
+    use super::*;
+    pub fn create(bytes: &[i8]) -> EncryptedDeks {
+        EncryptedDeks(i8_conv(bytes).to_vec())
+    }
+    pub fn bytes(e: &EncryptedDeks) -> Vec<i8> {
+        u8_conv(&e.0).to_vec()
+    }
+}
+
+/// Access changes made to one document by a bulk grant or revoke.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentBulkAccessEntry {
+    id: DocumentId,
+    succeeded: Vec<UserOrGroup>,
+    failed: Vec<DocAccessEditErr>,
+    error: Option<String>,
+}
+
+/// Access changes made to one unmanaged document by a bulk grant or revoke. `encrypted_deks`
+/// is `None`, and `error` is set, if the whole request for the document failed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentBulkAccessUnmanagedEntry {
+    encrypted_deks: Option<EncryptedDeks>,
+    succeeded: Vec<UserOrGroup>,
+    failed: Vec<DocAccessEditErr>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentBulkAccessResult(Vec<DocumentBulkAccessEntry>);
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentBulkAccessUnmanagedResult(Vec<DocumentBulkAccessUnmanagedEntry>);
+
+mod document_bulk_access_result {
+    use super::*;
+    pub fn id(e: &DocumentBulkAccessEntry) -> DocumentId {
+        e.id.clone()
+    }
+    pub fn encrypted_deks(e: &DocumentBulkAccessUnmanagedEntry) -> Option<EncryptedDeks> {
+        e.encrypted_deks.clone()
+    }
+    pub fn result(r: &DocumentBulkAccessResult) -> Vec<DocumentBulkAccessEntry> {
+        r.0.clone()
+    }
+    pub fn unmanaged_result(
+        r: &DocumentBulkAccessUnmanagedResult,
+    ) -> Vec<DocumentBulkAccessUnmanagedEntry> {
+        r.0.clone()
+    }
+    pub fn error(e: &DocumentBulkAccessEntry) -> Option<String> {
+        e.error.clone()
+    }
+    pub fn unmanaged_error(e: &DocumentBulkAccessUnmanagedEntry) -> Option<String> {
+        e.error.clone()
+    }
+    pub fn has_failures(r: &DocumentBulkAccessResult) -> bool {
+        r.0.iter()
+            .any(|e| e.error.is_some() || !e.failed.is_empty())
+    }
+    pub fn unmanaged_has_failures(r: &DocumentBulkAccessUnmanagedResult) -> bool {
+        r.0.iter()
+            .any(|e| e.error.is_some() || !e.failed.is_empty())
+    }
+
+    /// Report a request for a document that failed outright as a failure for everyone in it. The
+    /// entry's `error` holds the same reason, so it isn't lost when nobody was in the request.
+    pub fn failed_for_all(
+        users_and_groups: &[UserOrGroup],
+        err: &IronOxideErr,
+    ) -> Vec<DocAccessEditErr> {
+        users_and_groups
+            .iter()
+            .map(|user_or_group| DocAccessEditErr {
+                user_or_group: user_or_group.clone(),
+                err: err.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl document_access_change_result::DocumentAccessChange for DocumentBulkAccessEntry {
+    fn changed(&self) -> document_access_change_result::SucceededResult {
+        document_access_change_result::to_succeeded_result(&self.succeeded)
+    }
+
+    fn errors(&self) -> document_access_change_result::FailedResult {
+        document_access_change_result::to_failed_result(&self.failed)
+    }
+}
+
+impl document_access_change_result::DocumentAccessChange for DocumentBulkAccessUnmanagedEntry {
     fn changed(&self) -> document_access_change_result::SucceededResult {
-        document_access_change_result::to_succeeded_result(self.succeeded())
+        document_access_change_result::to_succeeded_result(&self.succeeded)
     }
 
     fn errors(&self) -> document_access_change_result::FailedResult {
-        document_access_change_result::to_failed_result(self.failed())
+        document_access_change_result::to_failed_result(&self.failed)
     }
 }
 
 // UserAccessErr and GroupAccessErr are a Java-compatible representation of IronOxide's
 // DocAccessEditErr. They are encoded this this because this seemed like the most
 // straightforward way to represent a error for both a user or group (like UserOrGroup)
-#[derive(Clone, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct UserAccessErr {
     id: UserId,
     err: String,
@@ -676,7 +3866,7 @@ impl UserAccessErr {
 
 /// Wrap the Vec<UserId> type in a newtype because swig can't handle
 /// passing through an Option<Vec<*>> for GroupGetResult
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Debug, Eq, Hash, PartialEq)]
 pub struct GroupUserList(Vec<UserId>);
 impl GroupUserList {
     pub fn list(&self) -> Vec<UserId> {
@@ -684,7 +3874,7 @@ impl GroupUserList {
     }
 }
 
-#[derive(Clone, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct GroupAccessErr {
     id: GroupId,
     err: String,
@@ -704,7 +3894,7 @@ mod document_access_change_result {
     use super::*;
     use itertools::{Either, Itertools};
 
-    #[derive(Eq, Hash, PartialEq)]
+    #[derive(Debug, Eq, Hash, PartialEq)]
     pub struct SucceededResult {
         users: Vec<UserId>,
         groups: Vec<GroupId>,
@@ -722,7 +3912,7 @@ mod document_access_change_result {
         }
     }
 
-    #[derive(Eq, Hash, PartialEq)]
+    #[derive(Debug, Eq, Hash, PartialEq)]
     pub struct FailedResult {
         users: Vec<UserAccessErr>,
         groups: Vec<GroupAccessErr>,
@@ -866,6 +4056,218 @@ mod group_list_result {
     }
 }
 
+/// Which groups a filtered group listing returns. Every condition that's set has to match.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GroupListFilter {
+    admin_only: bool,
+    member_only: bool,
+    needs_rotation_only: bool,
+    name: Option<GroupName>,
+}
+impl GroupListFilter {
+    fn matches(&self, g: &GroupMetaResult) -> bool {
+        (!self.admin_only || g.is_admin())
+            && (!self.member_only || g.is_member())
+            && (!self.needs_rotation_only || g.needs_rotation() == Some(true))
+            && self.name.as_ref().is_none_or(|name| g.name() == Some(name))
+    }
+}
+
+mod group_list_filter {
+    use super::*;
+    pub fn create(
+        admin_only: bool,
+        member_only: bool,
+        needs_rotation_only: bool,
+        name: Option<&GroupName>,
+    ) -> GroupListFilter {
+        GroupListFilter {
+            admin_only,
+            member_only,
+            needs_rotation_only,
+            name: name.cloned(),
+        }
+    }
+}
+
+/// The groups with a particular name. Names aren't unique, so there may be more than one.
+#[derive(Debug)]
+pub struct GroupNameResolution(Vec<GroupId>);
+
+mod group_name_resolution {
+    use super::*;
+    pub fn id(r: &GroupNameResolution) -> Option<GroupId> {
+        match r.0.as_slice() {
+            [id] => Some(id.clone()),
+            _ => None,
+        }
+    }
+    pub fn matches(r: &GroupNameResolution) -> Vec<GroupId> {
+        r.0.clone()
+    }
+    pub fn is_ambiguous(r: &GroupNameResolution) -> bool {
+        r.0.len() > 1
+    }
+}
+
+/// A single change made to a group's admin or member list by a membership sync.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GroupSyncChange {
+    AddAdmin,
+    RemoveAdmin,
+    AddMember,
+    RemoveMember,
+}
+
+/// A change planned by a membership sync for one user, and the error if applying it failed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GroupSyncChangeResult {
+    user: UserId,
+    change: GroupSyncChange,
+    error: Option<String>,
+}
+
+mod group_sync_change_result {
+    use super::*;
+    pub fn user(r: &GroupSyncChangeResult) -> UserId {
+        r.user.clone()
+    }
+    pub fn change(r: &GroupSyncChangeResult) -> GroupSyncChange {
+        r.change
+    }
+    pub fn error(r: &GroupSyncChangeResult) -> Option<String> {
+        r.error.clone()
+    }
+}
+
+/// Every change a membership sync planned, in the order they're applied.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GroupSyncResult {
+    dry_run: bool,
+    changes: Vec<GroupSyncChangeResult>,
+}
+
+mod group_sync_result {
+    use super::*;
+    pub fn is_dry_run(r: &GroupSyncResult) -> bool {
+        r.dry_run
+    }
+    pub fn changes(r: &GroupSyncResult) -> Vec<GroupSyncChangeResult> {
+        r.changes.clone()
+    }
+    pub fn succeeded(r: &GroupSyncResult) -> Vec<GroupSyncChangeResult> {
+        if r.dry_run {
+            vec![]
+        } else {
+            r.changes
+                .iter()
+                .filter(|c| c.error.is_none())
+                .cloned()
+                .collect()
+        }
+    }
+    pub fn failed(r: &GroupSyncResult) -> Vec<GroupSyncChangeResult> {
+        r.changes
+            .iter()
+            .filter(|c| c.error.is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+/// A kind of change made when offboarding a user.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OffboardAction {
+    RemoveGroupMember,
+    RemoveGroupAdmin,
+    RevokeDocumentAccess,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum OffboardStep {
+    RemoveGroupMember(GroupId),
+    RemoveGroupAdmin(GroupId),
+    RevokeDocumentAccess(DocumentId),
+}
+
+/// One change made when offboarding a user, and the error if it failed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OffboardChange {
+    step: OffboardStep,
+    error: Option<String>,
+}
+
+mod offboard_change {
+    use super::*;
+    pub fn action(c: &OffboardChange) -> OffboardAction {
+        match c.step {
+            OffboardStep::RemoveGroupMember(_) => OffboardAction::RemoveGroupMember,
+            OffboardStep::RemoveGroupAdmin(_) => OffboardAction::RemoveGroupAdmin,
+            OffboardStep::RevokeDocumentAccess(_) => OffboardAction::RevokeDocumentAccess,
+        }
+    }
+    pub fn group_id(c: &OffboardChange) -> Option<GroupId> {
+        match &c.step {
+            OffboardStep::RemoveGroupMember(id) | OffboardStep::RemoveGroupAdmin(id) => {
+                Some(id.clone())
+            }
+            OffboardStep::RevokeDocumentAccess(_) => None,
+        }
+    }
+    pub fn document_id(c: &OffboardChange) -> Option<DocumentId> {
+        match &c.step {
+            OffboardStep::RevokeDocumentAccess(id) => Some(id.clone()),
+            _ => None,
+        }
+    }
+    pub fn error(c: &OffboardChange) -> Option<String> {
+        c.error.clone()
+    }
+}
+
+/// Everything done, or planned on a dry run, to offboard a user.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OffboardReport {
+    user: UserId,
+    dry_run: bool,
+    changes: Vec<OffboardChange>,
+    lookup_errors: Vec<String>,
+}
+
+mod offboard_report {
+    use super::*;
+    pub fn user(r: &OffboardReport) -> UserId {
+        r.user.clone()
+    }
+    pub fn is_dry_run(r: &OffboardReport) -> bool {
+        r.dry_run
+    }
+    pub fn changes(r: &OffboardReport) -> Vec<OffboardChange> {
+        r.changes.clone()
+    }
+    pub fn succeeded(r: &OffboardReport) -> Vec<OffboardChange> {
+        if r.dry_run {
+            vec![]
+        } else {
+            r.changes
+                .iter()
+                .filter(|c| c.error.is_none())
+                .cloned()
+                .collect()
+        }
+    }
+    pub fn failed(r: &OffboardReport) -> Vec<OffboardChange> {
+        r.changes
+            .iter()
+            .filter(|c| c.error.is_some())
+            .cloned()
+            .collect()
+    }
+    pub fn lookup_errors(r: &OffboardReport) -> Vec<String> {
+        r.lookup_errors.clone()
+    }
+}
+
 mod group_get_result {
     use super::*;
     pub fn id(g: &GroupGetResult) -> GroupId {
@@ -904,8 +4306,74 @@ mod group_update_private_key_result {
     }
 }
 
+/// Settings for a `GroupCreateOpts`, which can be set one at a time before it's built.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GroupCreateOptsBuilder {
+    id: Option<GroupId>,
+    name: Option<GroupName>,
+    add_as_admin: bool,
+    add_as_member: bool,
+    owner: Option<UserId>,
+    admins: Vec<UserId>,
+    members: Vec<UserId>,
+    needs_rotation: bool,
+}
+impl Default for GroupCreateOptsBuilder {
+    /// The creating user is the group's owner, only admin and only member, as with IronOxide's
+    /// default.
+    fn default() -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            id: None,
+            name: None,
+            add_as_admin: true,
+            add_as_member: true,
+            owner: None,
+            admins: vec![],
+            members: vec![],
+            needs_rotation: false,
+        }
+    }
+}
+
+/// IronOxide's `GroupCreateOpts` along with the settings it was created from, which IronOxide
+/// doesn't expose.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GroupCreateOpts {
+    sdk: ironoxide::group::GroupCreateOpts,
+    settings: GroupCreateOptsBuilder,
+}
+impl Default for GroupCreateOpts {
+    fn default() -> GroupCreateOpts {
+        GroupCreateOptsBuilder::default().into()
+    }
+}
+impl From<GroupCreateOptsBuilder> for GroupCreateOpts {
+    fn from(settings: GroupCreateOptsBuilder) -> GroupCreateOpts {
+        GroupCreateOpts {
+            sdk: ironoxide::group::GroupCreateOpts::new(
+                settings.id.clone(),
+                settings.name.clone(),
+                settings.add_as_admin,
+                settings.add_as_member,
+                settings.owner.clone(),
+                settings.admins.clone(),
+                settings.members.clone(),
+                settings.needs_rotation,
+            ),
+            settings,
+        }
+    }
+}
+impl Deref for GroupCreateOpts {
+    type Target = ironoxide::group::GroupCreateOpts;
+    fn deref(&self) -> &ironoxide::group::GroupCreateOpts {
+        &self.sdk
+    }
+}
+
 mod group_create_opts {
     use super::*;
+    #[allow(clippy::too_many_arguments)]
     pub fn create(
         id: Option<&GroupId>,
         name: Option<&GroupName>,
@@ -916,16 +4384,111 @@ mod group_create_opts {
         members: &[UserId],
         needs_rotation: bool,
     ) -> GroupCreateOpts {
-        GroupCreateOpts::new(
-            id.cloned(),
-            name.cloned(),
+        GroupCreateOptsBuilder {
+            id: id.cloned(),
+            name: name.cloned(),
             add_as_admin,
             add_as_member,
-            owner.cloned(),
-            admins.to_vec(),
-            members.to_vec(),
+            owner: owner.cloned(),
+            admins: admins.to_vec(),
+            members: members.to_vec(),
             needs_rotation,
-        )
+        }
+        .into()
+    }
+    pub fn id(o: &GroupCreateOpts) -> Option<GroupId> {
+        o.settings.id.clone()
+    }
+    pub fn name(o: &GroupCreateOpts) -> Option<GroupName> {
+        o.settings.name.clone()
+    }
+    pub fn add_as_admin(o: &GroupCreateOpts) -> bool {
+        o.settings.add_as_admin
+    }
+    pub fn add_as_member(o: &GroupCreateOpts) -> bool {
+        o.settings.add_as_member
+    }
+    pub fn owner(o: &GroupCreateOpts) -> Option<UserId> {
+        o.settings.owner.clone()
+    }
+    pub fn admins(o: &GroupCreateOpts) -> Vec<UserId> {
+        o.settings.admins.clone()
+    }
+    pub fn members(o: &GroupCreateOpts) -> Vec<UserId> {
+        o.settings.members.clone()
+    }
+    pub fn needs_rotation(o: &GroupCreateOpts) -> bool {
+        o.settings.needs_rotation
+    }
+}
+
+mod group_create_opts_builder {
+    use super::*;
+    pub fn id(b: &GroupCreateOptsBuilder, id: &GroupId) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            id: Some(id.clone()),
+            ..b.clone()
+        }
+    }
+    pub fn name(b: &GroupCreateOptsBuilder, name: &GroupName) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            name: Some(name.clone()),
+            ..b.clone()
+        }
+    }
+    pub fn add_as_admin(b: &GroupCreateOptsBuilder, add_as_admin: bool) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            add_as_admin,
+            ..b.clone()
+        }
+    }
+    pub fn add_as_member(
+        b: &GroupCreateOptsBuilder,
+        add_as_member: bool,
+    ) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            add_as_member,
+            ..b.clone()
+        }
+    }
+    pub fn owner(b: &GroupCreateOptsBuilder, owner: &UserId) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            owner: Some(owner.clone()),
+            ..b.clone()
+        }
+    }
+    pub fn admins(b: &GroupCreateOptsBuilder, admins: &[UserId]) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            admins: admins.to_vec(),
+            ..b.clone()
+        }
+    }
+    pub fn members(b: &GroupCreateOptsBuilder, members: &[UserId]) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            members: members.to_vec(),
+            ..b.clone()
+        }
+    }
+    pub fn needs_rotation(
+        b: &GroupCreateOptsBuilder,
+        needs_rotation: bool,
+    ) -> GroupCreateOptsBuilder {
+        GroupCreateOptsBuilder {
+            needs_rotation,
+            ..b.clone()
+        }
+    }
+    /// Fails if the group is certain to have no admins. Whether the creating user ends up an admin
+    /// when they're only named in `admins` is checked by IronOxide, which knows who they are.
+    pub fn build(b: &GroupCreateOptsBuilder) -> Result<GroupCreateOpts, String> {
+        if !b.add_as_admin && b.owner.is_none() && b.admins.is_empty() {
+            return Err(
+                "GroupCreateOpts must have an admin. Set addAsAdmin, an owner or a \
+                list of admins."
+                    .to_string(),
+            );
+        }
+        Ok(b.clone().into())
     }
 }
 
@@ -944,17 +4507,125 @@ mod ironoxide_config {
     pub fn create(
         policy_caching: &PolicyCachingConfig,
         sdk_operation_timeout: Option<&Duration>,
+    ) -> IronOxideConfig {
+        create_with_retry(
+            policy_caching,
+            sdk_operation_timeout,
+            &RetryPolicy::default(),
+        )
+    }
+    pub fn create_with_retry(
+        policy_caching: &PolicyCachingConfig,
+        sdk_operation_timeout: Option<&Duration>,
+        retry_policy: &RetryPolicy,
     ) -> IronOxideConfig {
         IronOxideConfig {
-            policy_caching: policy_caching.clone(),
-            sdk_operation_timeout: sdk_operation_timeout.copied(),
+            sdk: ironoxide::config::IronOxideConfig {
+                policy_caching: policy_caching.clone(),
+                sdk_operation_timeout: sdk_operation_timeout.copied(),
+            },
+            retry_policy: retry_policy.clone(),
         }
     }
     pub fn get_policy_caching(ioc: &IronOxideConfig) -> PolicyCachingConfig {
-        ioc.policy_caching.clone()
+        ioc.sdk.policy_caching.clone()
     }
     pub fn get_timeout(ioc: &IronOxideConfig) -> Option<Duration> {
-        ioc.sdk_operation_timeout
+        ioc.sdk.sdk_operation_timeout
+    }
+    pub fn get_retry_policy(ioc: &IronOxideConfig) -> RetryPolicy {
+        ioc.retry_policy.clone()
+    }
+}
+
+/// An `IronOxideConfig` whose settings can be changed one at a time before it's built.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IronOxideConfigBuilder(IronOxideConfig);
+
+mod ironoxide_config_builder {
+    use super::*;
+    pub fn policy_caching(
+        b: &IronOxideConfigBuilder,
+        policy_caching: &PolicyCachingConfig,
+    ) -> IronOxideConfigBuilder {
+        let mut config = b.0.clone();
+        config.sdk.policy_caching = policy_caching.clone();
+        IronOxideConfigBuilder(config)
+    }
+    pub fn sdk_operation_timeout(
+        b: &IronOxideConfigBuilder,
+        sdk_operation_timeout: Option<&Duration>,
+    ) -> IronOxideConfigBuilder {
+        let mut config = b.0.clone();
+        config.sdk.sdk_operation_timeout = sdk_operation_timeout.copied();
+        IronOxideConfigBuilder(config)
+    }
+    pub fn retry_policy(
+        b: &IronOxideConfigBuilder,
+        retry_policy: &RetryPolicy,
+    ) -> IronOxideConfigBuilder {
+        let mut config = b.0.clone();
+        config.retry_policy = retry_policy.clone();
+        IronOxideConfigBuilder(config)
+    }
+    /// Fails on a zero timeout, which would make every SDK call time out.
+    pub fn build(b: &IronOxideConfigBuilder) -> Result<IronOxideConfig, String> {
+        if b.0.sdk.sdk_operation_timeout == Some(Duration::ZERO) {
+            return Err(
+                "sdkOperationTimeout must be greater than zero. Use null for no timeout."
+                    .to_string(),
+            );
+        }
+        if b.0.retry_policy.initial_backoff > b.0.retry_policy.max_backoff {
+            return Err(
+                "The retry policy's initialBackoff can't be more than its maxBackoff.".to_string(),
+            );
+        }
+        Ok(b.0.clone())
+    }
+}
+
+mod retry_policy {
+    use super::*;
+    pub fn create(
+        max_attempts: u32,
+        initial_backoff: &Duration,
+        max_backoff: &Duration,
+        retry_server_errors: bool,
+        retry_connection_errors: bool,
+        retry_timeouts: bool,
+        idempotent_only: bool,
+    ) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: *initial_backoff,
+            max_backoff: *max_backoff,
+            retry_server_errors,
+            retry_connection_errors,
+            retry_timeouts,
+            idempotent_only,
+        }
+    }
+    pub fn get_max_attempts(rp: &RetryPolicy) -> u32 {
+        rp.max_attempts
+    }
+    pub fn get_initial_backoff(rp: &RetryPolicy) -> Duration {
+        rp.initial_backoff
+    }
+    pub fn get_max_backoff(rp: &RetryPolicy) -> Duration {
+        rp.max_backoff
+    }
+    pub fn retry_server_errors(rp: &RetryPolicy) -> bool {
+        rp.retry_server_errors
+    }
+    pub fn retry_connection_errors(rp: &RetryPolicy) -> bool {
+        rp.retry_connection_errors
+    }
+    pub fn retry_timeouts(rp: &RetryPolicy) -> bool {
+        rp.retry_timeouts
+    }
+    pub fn idempotent_only(rp: &RetryPolicy) -> bool {
+        rp.idempotent_only
     }
 }
 
@@ -1021,107 +4692,306 @@ mod jwt {
     }
 }
 
+fn users_and_groups(users: &[UserId], groups: &[GroupId]) -> Vec<UserOrGroup> {
+    users
+        .iter()
+        .cloned()
+        .map(|u| UserOrGroup::User { id: u })
+        .chain(groups.iter().cloned().map(|g| UserOrGroup::Group { id: g }))
+        .collect()
+}
+
 //Java SDK wrapper functions for doing unnatural things with the JNI.
-fn user_verify(jwt: &Jwt, timeout: Option<&Duration>) -> Result<Option<UserResult>, String> {
-    Ok(IronOxide::user_verify(jwt, timeout.copied())?)
+fn user_verify(jwt: &Jwt, timeout: Option<&Duration>) -> Result<Option<UserResult>, Error> {
+    user_verify_with_retry(jwt, timeout, &RetryPolicy::default())
+}
+fn user_verify_with_retry(
+    jwt: &Jwt,
+    timeout: Option<&Duration>,
+    retry_policy: &RetryPolicy,
+) -> Result<Option<UserResult>, Error> {
+    platform::check_initialized()?;
+    Ok(retry_policy.run(true, || {
+        BlockingIronOxide::user_verify(jwt, timeout.copied())
+    })?)
 }
 fn user_create(
     jwt: &Jwt,
     password: &str,
     opts: &UserCreateOpts,
     timeout: Option<&Duration>,
-) -> Result<UserCreateResult, String> {
-    Ok(IronOxide::user_create(
-        jwt,
-        password,
-        opts,
-        timeout.copied(),
-    )?)
+) -> Result<UserCreateResult, Error> {
+    user_create_with_retry(jwt, password, opts, timeout, &RetryPolicy::default())
 }
-fn initialize(init: &DeviceContext, config: &IronOxideConfig) -> Result<IronOxide, String> {
-    Ok(ironoxide::blocking::initialize(init, config)?)
+fn user_create_with_retry(
+    jwt: &Jwt,
+    password: &str,
+    opts: &UserCreateOpts,
+    timeout: Option<&Duration>,
+    retry_policy: &RetryPolicy,
+) -> Result<UserCreateResult, Error> {
+    platform::check_initialized()?;
+    Ok(retry_policy.run(false, || {
+        BlockingIronOxide::user_create(jwt, password, opts, timeout.copied())
+    })?)
+}
+fn initialize(init: &DeviceContext, config: &IronOxideConfig) -> Result<IronOxide, Error> {
+    platform::check_initialized()?;
+    let sdk = config
+        .retry_policy
+        .run(true, || ironoxide::blocking::initialize(init, &config.sdk))?;
+    Ok(IronOxide::new(sdk, &config.retry_policy))
 }
 fn initialize_and_rotate(
     init: &DeviceContext,
     password: &str,
     config: &IronOxideConfig,
     timeout: Option<&Duration>,
-) -> Result<IronOxide, String> {
-    let rotate_timeout = timeout.copied().or(config.sdk_operation_timeout);
-    Ok(
-        match ironoxide::blocking::initialize_check_rotation(init, config)? {
-            InitAndRotationCheck::RotationNeeded(ironoxide, rotation) => {
+) -> Result<IronOxide, Error> {
+    platform::check_initialized()?;
+    let rotate_timeout = timeout.copied().or(config.sdk.sdk_operation_timeout);
+    let init_check = config.retry_policy.run(true, || {
+        ironoxide::blocking::initialize_check_rotation(init, &config.sdk)
+    })?;
+    Ok(IronOxide::new(
+        match init_check {
+            ironoxide::InitAndRotationCheck::RotationNeeded(ironoxide, rotation) => {
                 ironoxide.rotate_all(&rotation, password, rotate_timeout)?;
                 ironoxide
             }
-            InitAndRotationCheck::NoRotationNeeded(ironoxide) => ironoxide,
+            ironoxide::InitAndRotationCheck::NoRotationNeeded(ironoxide) => ironoxide,
         },
-    )
+        &config.retry_policy,
+    ))
+}
+fn initialize_check_rotation(
+    init: &DeviceContext,
+    config: &IronOxideConfig,
+) -> Result<InitAndRotationCheck, Error> {
+    platform::check_initialized()?;
+    let init_check = config.retry_policy.run(true, || {
+        ironoxide::blocking::initialize_check_rotation(init, &config.sdk)
+    })?;
+    Ok(InitAndRotationCheck::new(init_check, &config.retry_policy))
+}
+fn initialize_with_public_keys_and_check_rotation(
+    init: &DeviceContext,
+    config: &IronOxideConfig,
+    public_key_cache: &[i8],
+) -> Result<InitAndRotationCheck, Error> {
+    platform::check_initialized()?;
+    let init_check = config.retry_policy.run(true, || {
+        ironoxide::blocking::initialize_with_public_keys_and_check_rotation(
+            init,
+            &config.sdk,
+            i8_conv(public_key_cache).to_vec(),
+        )
+    })?;
+    Ok(InitAndRotationCheck::new(init_check, &config.retry_policy))
+}
+fn rotate_all(
+    sdk: &IronOxide,
+    rotations: &PrivateKeyRotationCheckResult,
+    password: &str,
+    timeout: Option<&Duration>,
+) -> Result<RotateAllResult, Error> {
+    let (user, groups) = sdk.retry(false, |sdk| {
+        sdk.rotate_all(rotations, password, timeout.copied())
+    })?;
+    Ok(RotateAllResult {
+        user,
+        groups: groups.unwrap_or_default(),
+    })
 }
 fn generate_new_device(
     jwt: &Jwt,
     password: &str,
     opts: &DeviceCreateOpts,
     timeout: Option<&Duration>,
-) -> Result<DeviceAddResult, String> {
-    Ok(IronOxide::generate_new_device(
-        jwt,
-        password,
-        opts,
-        timeout.copied(),
-    )?)
-}
-fn user_list_devices(sdk: &IronOxide) -> Result<UserDeviceListResult, String> {
-    Ok(sdk.user_list_devices()?)
-}
-fn user_get_public_key(sdk: &IronOxide, users: &[UserId]) -> Result<Vec<UserWithKey>, String> {
-    let result = sdk.user_get_public_key(users)?;
+) -> Result<DeviceAddResult, Error> {
+    generate_new_device_with_retry(jwt, password, opts, timeout, &RetryPolicy::default())
+}
+fn generate_new_device_with_retry(
+    jwt: &Jwt,
+    password: &str,
+    opts: &DeviceCreateOpts,
+    timeout: Option<&Duration>,
+    retry_policy: &RetryPolicy,
+) -> Result<DeviceAddResult, Error> {
+    platform::check_initialized()?;
+    Ok(retry_policy.run(false, || {
+        BlockingIronOxide::generate_new_device(jwt, password, opts, timeout.copied())
+    })?)
+}
+fn user_list_devices(sdk: &IronOxide) -> Result<UserDeviceListResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.user_list_devices())?)
+}
+fn user_get_public_key(sdk: &IronOxide, users: &[UserId]) -> Result<Vec<UserWithKey>, Error> {
+    let result = sdk.retry(true, |sdk| sdk.user_get_public_key(users))?;
     Ok(result.into_iter().map(UserWithKey).collect())
 }
-fn user_delete_device(sdk: &IronOxide, device_id: Option<&DeviceId>) -> Result<DeviceId, String> {
-    Ok(sdk.user_delete_device(device_id)?)
+fn user_delete_device(sdk: &IronOxide, device_id: Option<&DeviceId>) -> Result<DeviceId, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.user_delete_device(device_id))?)
+}
+fn user_delete_other_devices(sdk: &IronOxide) -> Result<DeviceDeleteReport, Error> {
+    delete_devices(sdk, |_| true)
+}
+fn user_delete_devices_not_updated_since(
+    sdk: &IronOxide,
+    since_millis: i64,
+) -> Result<DeviceDeleteReport, Error> {
+    delete_devices(sdk, |d| unix_millis(d.last_updated()) < since_millis)
+}
+/// Delete the user's devices that match `filter`, one at a time. The current device is never
+/// deleted, as the SDK couldn't be used afterwards.
+fn delete_devices<F>(sdk: &IronOxide, filter: F) -> Result<DeviceDeleteReport, Error>
+where
+    F: Fn(&UserDevice) -> bool,
+{
+    let devices = sdk.retry(true, |sdk| sdk.user_list_devices())?;
+    Ok(DeviceDeleteReport(
+        devices
+            .result()
+            .iter()
+            .filter(|d| !d.is_current_device() && filter(d))
+            .map(|d| DeviceDeleteOutcome {
+                device: d.clone(),
+                error: sdk
+                    .retry(false, |sdk| sdk.user_delete_device(Some(d.id())))
+                    .err()
+                    .map(|e| e.to_string()),
+            })
+            .collect(),
+    ))
 }
 fn user_rotate_private_key(
     sdk: &IronOxide,
     password: &str,
-) -> Result<UserUpdatePrivateKeyResult, String> {
-    Ok(sdk.user_rotate_private_key(password)?)
+) -> Result<UserUpdatePrivateKeyResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.user_rotate_private_key(password))?)
 }
-fn document_list(sdk: &IronOxide) -> Result<DocumentListResult, String> {
-    Ok(sdk.document_list()?)
+fn user_change_password(
+    sdk: &IronOxide,
+    current_password: &str,
+    new_password: &str,
+) -> Result<UserUpdateResult, Error> {
+    let result = sdk.retry(false, |sdk| {
+        sdk.user_change_password(current_password, new_password)
+    })?;
+    Ok(UserUpdateResult(result))
+}
+fn document_list(sdk: &IronOxide) -> Result<DocumentListResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.document_list())?)
+}
+fn document_list_page(
+    sdk: &IronOxide,
+    filter: &DocumentListFilter,
+    page_size: usize,
+) -> Result<DocumentListPage, Error> {
+    if page_size == 0 {
+        return Err(Error::validation("pageSize must be greater than 0."));
+    }
+    Ok(DocumentListPage {
+        documents: filtered_documents(sdk, filter)?.into(),
+        start: 0,
+        page_size,
+    })
+}
+fn document_list_cursor(
+    sdk: &IronOxide,
+    filter: &DocumentListFilter,
+) -> Result<DocumentListCursor, Error> {
+    Ok(DocumentListCursor(
+        filtered_documents(sdk, filter)?.into_iter(),
+    ))
+}
+/// Expand the groups a document is shared with into their members, so the report lists every
+/// user who can decrypt the document and whether they can do so directly, through groups, or
+/// both. Only members can decrypt through a group, so group admins aren't included.
+fn document_explain_access(sdk: &IronOxide, id: &DocumentId) -> Result<AccessReport, Error> {
+    let document = sdk.retry(true, |sdk| sdk.document_get_metadata(id))?;
+    let mut users: Vec<AccessReportUser> = document
+        .visible_to_users()
+        .iter()
+        .map(|u| AccessReportUser {
+            id: u.id().clone(),
+            direct: true,
+            via_groups: vec![],
+        })
+        .collect();
+    let mut positions: HashMap<UserId, usize> = users
+        .iter()
+        .enumerate()
+        .map(|(i, u)| (u.id.clone(), i))
+        .collect();
+    let mut groups = vec![];
+    for visible_group in document.visible_to_groups() {
+        let group_id = visible_group.id();
+        // a group the caller can't read only leaves its members out, rather than failing the report
+        let (members, error) = match sdk.retry(true, |sdk| sdk.group_get_metadata(group_id)) {
+            Ok(group) => (group.member_list().cloned(), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        for member in members.iter().flatten() {
+            let i = *positions.entry(member.clone()).or_insert_with(|| {
+                users.push(AccessReportUser {
+                    id: member.clone(),
+                    direct: false,
+                    via_groups: vec![],
+                });
+                users.len() - 1
+            });
+            users[i].via_groups.push(group_id.clone());
+        }
+        groups.push(AccessReportGroup {
+            id: group_id.clone(),
+            name: visible_group.name().cloned(),
+            members_visible: members.is_some(),
+            error,
+        });
+    }
+    Ok(AccessReport {
+        document_id: id.clone(),
+        users,
+        groups,
+    })
 }
 fn document_get_metadata(
     sdk: &IronOxide,
     id: &DocumentId,
-) -> Result<DocumentMetadataResult, String> {
-    Ok(sdk.document_get_metadata(id)?)
+) -> Result<DocumentMetadataResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.document_get_metadata(id))?)
 }
-fn document_get_id_from_bytes(sdk: &IronOxide, bytes: &[i8]) -> Result<DocumentId, String> {
+fn document_get_id_from_bytes(sdk: &IronOxide, bytes: &[i8]) -> Result<DocumentId, Error> {
     Ok(sdk.document_get_id_from_bytes(i8_conv(bytes))?)
 }
 fn document_encrypt(
     sdk: &IronOxide,
     data: &[i8],
     opts: &DocumentEncryptOpts,
-) -> Result<DocumentEncryptResult, String> {
-    Ok(sdk.document_encrypt(i8_conv(data).to_vec(), opts)?)
+) -> Result<DocumentEncryptResult, Error> {
+    Ok(sdk.retry(false, |sdk| {
+        sdk.document_encrypt(i8_conv(data).to_vec(), opts)
+    })?)
 }
 fn document_update_bytes(
     sdk: &IronOxide,
     document_id: &DocumentId,
     data: &[i8],
-) -> Result<DocumentEncryptResult, String> {
-    Ok(sdk.document_update_bytes(document_id, i8_conv(data).to_vec())?)
+) -> Result<DocumentEncryptResult, Error> {
+    Ok(sdk.retry(false, |sdk| {
+        sdk.document_update_bytes(document_id, i8_conv(data).to_vec())
+    })?)
 }
-fn document_decrypt(sdk: &IronOxide, data: &[i8]) -> Result<DocumentDecryptResult, String> {
-    Ok(sdk.document_decrypt(i8_conv(data))?)
+fn document_decrypt(sdk: &IronOxide, data: &[i8]) -> Result<DocumentDecryptResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.document_decrypt(i8_conv(data)))?)
 }
 fn document_update_name(
     sdk: &IronOxide,
     document_id: &DocumentId,
     name: Option<&DocumentName>,
-) -> Result<DocumentMetadataResult, String> {
-    Ok(sdk.document_update_name(document_id, name)?)
+) -> Result<DocumentMetadataResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.document_update_name(document_id, name))?)
 }
 
 fn document_grant_access(
@@ -1129,20 +4999,12 @@ fn document_grant_access(
     document_id: &DocumentId,
     grant_users: &[UserId],
     grant_groups: &[GroupId],
-) -> Result<DocumentAccessResult, String> {
-    let users_and_groups = grant_users
-        .iter()
-        .cloned()
-        .map(|u| UserOrGroup::User { id: u })
-        .chain(
-            grant_groups
-                .iter()
-                .cloned()
-                .map(|g| UserOrGroup::Group { id: g }),
-        )
-        .collect();
+) -> Result<DocumentAccessResult, Error> {
+    let users_and_groups = users_and_groups(grant_users, grant_groups);
 
-    Ok(sdk.document_grant_access(document_id, &users_and_groups)?)
+    Ok(sdk.retry(false, |sdk| {
+        sdk.document_grant_access(document_id, &users_and_groups)
+    })?)
 }
 
 fn document_revoke_access(
@@ -1150,109 +5012,645 @@ fn document_revoke_access(
     document_id: &DocumentId,
     revoke_users: &[UserId],
     revoke_groups: &[GroupId],
-) -> Result<DocumentAccessResult, String> {
-    let users_and_groups = revoke_users
+) -> Result<DocumentAccessResult, Error> {
+    let users_and_groups = users_and_groups(revoke_users, revoke_groups);
+
+    Ok(sdk.retry(false, |sdk| {
+        sdk.document_revoke_access(document_id, &users_and_groups)
+    })?)
+}
+fn document_grant_access_bulk(
+    sdk: &IronOxide,
+    document_ids: &[DocumentId],
+    grant_users: &[UserId],
+    grant_groups: &[GroupId],
+    max_concurrency: usize,
+) -> Result<DocumentBulkAccessResult, Error> {
+    document_access_bulk(
+        sdk,
+        document_ids,
+        &users_and_groups(grant_users, grant_groups),
+        max_concurrency,
+        |sdk, id, users_and_groups| sdk.document_grant_access(id, users_and_groups),
+    )
+}
+fn document_revoke_access_bulk(
+    sdk: &IronOxide,
+    document_ids: &[DocumentId],
+    revoke_users: &[UserId],
+    revoke_groups: &[GroupId],
+    max_concurrency: usize,
+) -> Result<DocumentBulkAccessResult, Error> {
+    document_access_bulk(
+        sdk,
+        document_ids,
+        &users_and_groups(revoke_users, revoke_groups),
+        max_concurrency,
+        |sdk, id, users_and_groups| sdk.document_revoke_access(id, users_and_groups),
+    )
+}
+fn document_access_bulk<F>(
+    sdk: &IronOxide,
+    document_ids: &[DocumentId],
+    users_and_groups: &[UserOrGroup],
+    max_concurrency: usize,
+    change: F,
+) -> Result<DocumentBulkAccessResult, Error>
+where
+    F: Fn(
+            &BlockingIronOxide,
+            &DocumentId,
+            &[UserOrGroup],
+        ) -> ironoxide::Result<DocumentAccessResult>
+        + Sync,
+{
+    check_concurrency(max_concurrency)?;
+    let entries = run_bounded(document_ids, max_concurrency, |id| {
+        let (succeeded, failed, error) =
+            match sdk.retry(false, |sdk| change(sdk, id, users_and_groups)) {
+                Ok(result) => (result.succeeded().to_vec(), result.failed().to_vec(), None),
+                Err(e) => (
+                    vec![],
+                    document_bulk_access_result::failed_for_all(users_and_groups, &e),
+                    Some(e.to_string()),
+                ),
+            };
+        DocumentBulkAccessEntry {
+            id: id.clone(),
+            succeeded,
+            failed,
+            error,
+        }
+    });
+    Ok(DocumentBulkAccessResult(entries))
+}
+fn group_list(sdk: &IronOxide) -> Result<GroupListResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.group_list())?)
+}
+fn group_list_filtered(
+    sdk: &IronOxide,
+    filter: &GroupListFilter,
+) -> Result<Vec<GroupMetaResult>, Error> {
+    let groups = sdk.retry(true, |sdk| sdk.group_list())?;
+    Ok(groups
+        .result()
+        .iter()
+        .filter(|g| filter.matches(g))
+        .cloned()
+        .collect())
+}
+fn group_resolve_name(sdk: &IronOxide, name: &GroupName) -> Result<GroupNameResolution, Error> {
+    let groups = sdk.retry(true, |sdk| sdk.group_list())?;
+    Ok(GroupNameResolution(
+        groups
+            .result()
+            .iter()
+            .filter(|g| g.name() == Some(name))
+            .map(|g| g.id().clone())
+            .collect(),
+    ))
+}
+/// Make a group's admins and members exactly `desired_admins` and `desired_members`, only adding
+/// and removing the users that differ. Additions are applied before removals, so a sync that
+/// fails partway through doesn't leave the group short of admins.
+fn group_sync_membership(
+    sdk: &IronOxide,
+    id: &GroupId,
+    desired_admins: &[UserId],
+    desired_members: &[UserId],
+    dry_run: bool,
+) -> Result<GroupSyncResult, Error> {
+    let group = sdk.retry(true, |sdk| sdk.group_get_metadata(id))?;
+    if !group.is_admin() {
+        return Err(Error::other(format!(
+            "Only an admin of group '{}' can sync its membership.",
+            id.id()
+        )));
+    }
+    let admins = group.admin_list().map_or(&[][..], |a| a.as_slice());
+    let members = group.member_list().map_or(&[][..], |m| m.as_slice());
+    // users in `wanted` that aren't in `current`, without duplicates and in the order given
+    fn missing(wanted: &[UserId], current: &[UserId]) -> Vec<UserId> {
+        let mut seen: HashSet<&UserId> = current.iter().collect();
+        wanted.iter().filter(|u| seen.insert(*u)).cloned().collect()
+    }
+    let plan = [
+        (GroupSyncChange::AddAdmin, missing(desired_admins, admins)),
+        (
+            GroupSyncChange::AddMember,
+            missing(desired_members, members),
+        ),
+        (
+            GroupSyncChange::RemoveMember,
+            missing(members, desired_members),
+        ),
+        (
+            GroupSyncChange::RemoveAdmin,
+            missing(admins, desired_admins),
+        ),
+    ];
+
+    let mut changes = vec![];
+    for (change, users) in plan {
+        if users.is_empty() {
+            continue;
+        }
+        let errors: HashMap<UserId, String> = if dry_run {
+            HashMap::new()
+        } else {
+            let applied = sdk.retry(false, |sdk| match change {
+                GroupSyncChange::AddAdmin => sdk.group_add_admins(id, &users),
+                GroupSyncChange::RemoveAdmin => sdk.group_remove_admins(id, &users),
+                GroupSyncChange::AddMember => sdk.group_add_members(id, &users),
+                GroupSyncChange::RemoveMember => sdk.group_remove_members(id, &users),
+            });
+            match applied {
+                Ok(result) => result
+                    .failed()
+                    .iter()
+                    .map(|f| (f.user().clone(), f.error().clone()))
+                    .collect(),
+                Err(e) => users.iter().map(|u| (u.clone(), e.to_string())).collect(),
+            }
+        };
+        changes.extend(users.into_iter().map(|user| GroupSyncChangeResult {
+            error: errors.get(&user).cloned(),
+            user,
+            change,
+        }));
+    }
+    Ok(GroupSyncResult { dry_run, changes })
+}
+/// Remove `user` from every group the caller is an admin of and revoke their direct access to
+/// every document the caller owns. Groups and documents are looked up and changed on up to
+/// `max_concurrency` threads at a time.
+fn offboard_user(
+    sdk: &IronOxide,
+    user: &UserId,
+    max_concurrency: usize,
+    dry_run: bool,
+) -> Result<OffboardReport, Error> {
+    check_concurrency(max_concurrency)?;
+    let groups: Vec<GroupId> = sdk
+        .retry(true, |sdk| sdk.group_list())?
+        .result()
         .iter()
-        .cloned()
-        .map(|u| UserOrGroup::User { id: u })
-        .chain(
-            revoke_groups
-                .iter()
-                .cloned()
-                .map(|g| UserOrGroup::Group { id: g }),
-        )
+        .filter(|g| g.is_admin())
+        .map(|g| g.id().clone())
+        .collect();
+    let documents: Vec<DocumentId> = sdk
+        .retry(true, |sdk| sdk.document_list())?
+        .result()
+        .iter()
+        .filter(|d| *d.association_type() == AssociationType::Owner)
+        .map(|d| d.id().clone())
         .collect();
 
-    Ok(sdk.document_revoke_access(document_id, &users_and_groups)?)
+    let mut steps = vec![];
+    let mut lookup_errors = vec![];
+    let group_lookups = run_bounded(&groups, max_concurrency, |id| {
+        sdk.retry(true, |sdk| sdk.group_get_metadata(id))
+    });
+    for (id, lookup) in groups.into_iter().zip(group_lookups) {
+        match lookup {
+            Ok(group) => {
+                if group.member_list().is_some_and(|m| m.contains(user)) {
+                    steps.push(OffboardStep::RemoveGroupMember(id.clone()));
+                }
+                if group.admin_list().is_some_and(|a| a.contains(user)) {
+                    steps.push(OffboardStep::RemoveGroupAdmin(id));
+                }
+            }
+            Err(e) => lookup_errors.push(format!("Could not check group '{}': {e}", id.id())),
+        }
+    }
+    let document_lookups = run_bounded(&documents, max_concurrency, |id| {
+        sdk.retry(true, |sdk| sdk.document_get_metadata(id))
+    });
+    for (id, lookup) in documents.into_iter().zip(document_lookups) {
+        match lookup {
+            Ok(document) => {
+                if document.visible_to_users().iter().any(|u| u.id() == user) {
+                    steps.push(OffboardStep::RevokeDocumentAccess(id));
+                }
+            }
+            Err(e) => lookup_errors.push(format!("Could not check document '{}': {e}", id.id())),
+        }
+    }
+
+    let errors = if dry_run {
+        vec![None; steps.len()]
+    } else {
+        run_bounded(&steps, max_concurrency, |step| {
+            offboard_step(sdk, user, step).err()
+        })
+    };
+    Ok(OffboardReport {
+        user: user.clone(),
+        dry_run,
+        changes: steps
+            .into_iter()
+            .zip(errors)
+            .map(|(step, error)| OffboardChange { step, error })
+            .collect(),
+        lookup_errors,
+    })
 }
-fn group_list(sdk: &IronOxide) -> Result<GroupListResult, String> {
-    Ok(sdk.group_list()?)
+fn offboard_step(sdk: &IronOxide, user: &UserId, step: &OffboardStep) -> Result<(), String> {
+    let users = [user.clone()];
+    let failure = match step {
+        OffboardStep::RemoveGroupMember(id) => sdk
+            .retry(false, |sdk| sdk.group_remove_members(id, &users))?
+            .failed()
+            .first()
+            .map(|f| f.error().clone()),
+        OffboardStep::RemoveGroupAdmin(id) => sdk
+            .retry(false, |sdk| sdk.group_remove_admins(id, &users))?
+            .failed()
+            .first()
+            .map(|f| f.error().clone()),
+        OffboardStep::RevokeDocumentAccess(id) => sdk
+            .retry(false, |sdk| {
+                sdk.document_revoke_access(id, &[UserOrGroup::from(user)])
+            })?
+            .failed()
+            .first()
+            .map(|f| f.err.clone()),
+    };
+    failure.map_or(Ok(()), Err)
 }
-fn group_get_metadata(sdk: &IronOxide, id: &GroupId) -> Result<GroupGetResult, String> {
-    Ok(sdk.group_get_metadata(id)?)
+fn group_get_metadata(sdk: &IronOxide, id: &GroupId) -> Result<GroupGetResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.group_get_metadata(id))?)
 }
-fn group_create(sdk: &IronOxide, opts: &GroupCreateOpts) -> Result<GroupCreateResult, String> {
-    Ok(sdk.group_create(opts)?)
+fn group_create(sdk: &IronOxide, opts: &GroupCreateOpts) -> Result<GroupCreateResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_create(opts))?)
 }
 fn group_update_name(
     sdk: &IronOxide,
     id: &GroupId,
     name: Option<&GroupName>,
-) -> Result<GroupMetaResult, String> {
-    Ok(sdk.group_update_name(id, name)?)
+) -> Result<GroupMetaResult, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.group_update_name(id, name))?)
 }
-fn group_delete(sdk: &IronOxide, id: &GroupId) -> Result<GroupId, String> {
-    Ok(sdk.group_delete(id)?)
+fn group_delete(sdk: &IronOxide, id: &GroupId) -> Result<GroupId, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_delete(id))?)
 }
 fn group_add_members(
     sdk: &IronOxide,
     group_id: &GroupId,
     users: &[UserId],
-) -> Result<GroupAccessEditResult, String> {
-    Ok(sdk.group_add_members(group_id, users)?)
+) -> Result<GroupAccessEditResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_add_members(group_id, users))?)
 }
 fn group_remove_members(
     sdk: &IronOxide,
     group_id: &GroupId,
     users: &[UserId],
-) -> Result<GroupAccessEditResult, String> {
-    Ok(sdk.group_remove_members(group_id, users)?)
+) -> Result<GroupAccessEditResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_remove_members(group_id, users))?)
 }
 fn group_add_admins(
     sdk: &IronOxide,
     group_id: &GroupId,
     users: &[UserId],
-) -> Result<GroupAccessEditResult, String> {
-    Ok(sdk.group_add_admins(group_id, users)?)
+) -> Result<GroupAccessEditResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_add_admins(group_id, users))?)
 }
 fn group_remove_admins(
     sdk: &IronOxide,
     group_id: &GroupId,
     users: &[UserId],
-) -> Result<GroupAccessEditResult, String> {
-    Ok(sdk.group_remove_admins(group_id, users)?)
+) -> Result<GroupAccessEditResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_remove_admins(group_id, users))?)
 }
 fn group_rotate_private_key(
     sdk: &IronOxide,
     group_id: &GroupId,
-) -> Result<GroupUpdatePrivateKeyResult, String> {
-    Ok(sdk.group_rotate_private_key(group_id)?)
+) -> Result<GroupUpdatePrivateKeyResult, Error> {
+    Ok(sdk.retry(false, |sdk| sdk.group_rotate_private_key(group_id))?)
 }
 
 fn document_encrypt_unmanaged(
     sdk: &IronOxide,
     data: &[i8],
     opts: &DocumentEncryptOpts,
-) -> Result<DocumentEncryptUnmanagedResult, String> {
-    Ok(sdk.document_encrypt_unmanaged(i8_conv(data).to_vec(), opts)?)
+) -> Result<DocumentEncryptUnmanagedResult, Error> {
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_encrypt_unmanaged(i8_conv(data).to_vec(), opts)
+    })?)
+}
+
+/// Re-encrypt a managed document as an unmanaged one, keeping its ID and granting access to the
+/// same users and groups. The plaintext never leaves Rust. The managed document and its grants are
+/// left alone: IronOxide can't delete it, and revoking here could leave the caller without either
+/// copy if the result never reached them, so cleaning up is up to the caller.
+fn document_managed_to_unmanaged(
+    sdk: &IronOxide,
+    encrypted_data: &[i8],
+) -> Result<DocumentEncryptUnmanagedResult, Error> {
+    let decrypted = sdk.retry(true, |sdk| sdk.document_decrypt(i8_conv(encrypted_data)))?;
+    let metadata = sdk.retry(true, |sdk| sdk.document_get_metadata(decrypted.id()))?;
+    let opts = same_grants(
+        decrypted.id(),
+        metadata.visible_to_users(),
+        metadata.visible_to_groups(),
+    );
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_encrypt_unmanaged(decrypted.decrypted_data().to_vec(), &opts)
+    })?)
+}
+/// Re-encrypt an unmanaged document as a managed one, keeping its ID and granting access to the
+/// same users and groups. The plaintext never leaves Rust. Creating the managed document fails if
+/// the ID is already taken, including by an earlier run of this conversion.
+fn document_unmanaged_to_managed(
+    sdk: &IronOxide,
+    encrypted_data: &[i8],
+    encrypted_deks: &[i8],
+) -> Result<DocumentEncryptResult, Error> {
+    let decrypted = sdk.retry(true, |sdk| {
+        sdk.document_decrypt_unmanaged(i8_conv(encrypted_data), i8_conv(encrypted_deks))
+    })?;
+    let metadata = sdk.retry(true, |sdk| {
+        sdk.document_get_metadata_unmanaged(i8_conv(encrypted_deks))
+    })?;
+    let opts = same_grants(
+        decrypted.id(),
+        metadata.visible_to_users(),
+        metadata.visible_to_groups(),
+    );
+    Ok(sdk.retry(false, |sdk| {
+        sdk.document_encrypt(decrypted.decrypted_data().to_vec(), &opts)
+    })?)
+}
+/// Options to encrypt a document with the given ID to exactly the given users and groups.
+fn same_grants(
+    id: &DocumentId,
+    users: &[VisibleUser],
+    groups: &[VisibleGroup],
+) -> DocumentEncryptOpts {
+    let users: Vec<UserId> = users.iter().map(|u| u.id().clone()).collect();
+    let groups: Vec<GroupId> = groups.iter().map(|g| g.id().clone()).collect();
+    document_create_opt::create(Some(id), None, false, &users, &groups, None)
+}
+
+/// Result of encrypting from one direct `ByteBuffer` into another. The encrypted bytes are only in
+/// the caller's output buffer; `length` is how many were written.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentEncryptDirectResult {
+    id: DocumentId,
+    length: usize,
+    encrypted_deks: Option<EncryptedDeks>,
+    succeeded: Vec<UserOrGroup>,
+    failed: Vec<DocAccessEditErr>,
+}
+
+/// Result of decrypting from one direct `ByteBuffer` into another.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DocumentDecryptDirectResult {
+    id: DocumentId,
+    length: usize,
+}
+
+impl document_access_change_result::DocumentAccessChange for DocumentEncryptDirectResult {
+    fn changed(&self) -> document_access_change_result::SucceededResult {
+        document_access_change_result::to_succeeded_result(&self.succeeded)
+    }
+
+    fn errors(&self) -> document_access_change_result::FailedResult {
+        document_access_change_result::to_failed_result(&self.failed)
+    }
+}
+
+mod document_direct_result {
+    use super::*;
+    pub fn id(r: &DocumentEncryptDirectResult) -> DocumentId {
+        r.id.clone()
+    }
+    pub fn length(r: &DocumentEncryptDirectResult) -> usize {
+        r.length
+    }
+    pub fn encrypted_deks(r: &DocumentEncryptDirectResult) -> Option<EncryptedDeks> {
+        r.encrypted_deks.clone()
+    }
+    pub fn decrypted_id(r: &DocumentDecryptDirectResult) -> DocumentId {
+        r.id.clone()
+    }
+    pub fn decrypted_length(r: &DocumentDecryptDirectResult) -> usize {
+        r.length
+    }
+}
+
+/// Reads and writes direct `java.nio.ByteBuffer`s in place, so document data doesn't have to be
+/// copied onto the Java heap. IronOxide still needs its own copy of the input, and returns its
+/// output as a `Vec`, so one copy of each is made in native memory.
+#[cfg(any(feature = "java", feature = "android"))]
+mod direct_buffer {
+    use super::*;
+
+    /// The memory of a direct `ByteBuffer`, from its address to its capacity. The Java wrappers
+    /// pass `slice()`s, so this is the buffer's remaining bytes. `data` is null if the buffer
+    /// wasn't direct.
+    pub struct DirectByteBuffer {
+        data: *mut u8,
+        len: usize,
+    }
+
+    impl DirectByteBuffer {
+        /// Used by the `DirectByteBuffer` typemap in jni_typemaps.rs.
+        pub fn new(env: *mut JNIEnv, buffer: jobject) -> DirectByteBuffer {
+            unsafe {
+                let data = (**env).GetDirectBufferAddress.unwrap()(env, buffer) as *mut u8;
+                let capacity = (**env).GetDirectBufferCapacity.unwrap()(env, buffer);
+                DirectByteBuffer {
+                    data,
+                    len: if data.is_null() {
+                        0
+                    } else {
+                        capacity.max(0) as usize
+                    },
+                }
+            }
+        }
+
+        fn check_direct(&self) -> Result<(), Error> {
+            if self.data.is_null() {
+                Err(Error::validation(
+                    "ByteBuffer must be direct. Use ByteBuffer.allocateDirect.",
+                ))
+            } else {
+                Ok(())
+            }
+        }
+
+        /// The JVM keeps the buffer alive for the duration of the native call.
+        pub fn as_slice(&self) -> Result<&[u8], Error> {
+            self.check_direct()?;
+            Ok(unsafe { std::slice::from_raw_parts(self.data, self.len) })
+        }
+
+        fn check_fits(&self, needed: usize) -> Result<(), Error> {
+            self.check_direct()?;
+            if needed > self.len {
+                return Err(Error::validation(format!(
+                    "Output ByteBuffer is too small. {needed} bytes are needed, but only {} are remaining.",
+                    self.len
+                )));
+            }
+            Ok(())
+        }
+
+        /// Copy `bytes` to the start of the buffer, returning how many were written.
+        pub fn write(&mut self, bytes: &[u8]) -> Result<usize, Error> {
+            self.check_fits(bytes.len())?;
+            unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), self.data, bytes.len()) };
+            Ok(bytes.len())
+        }
+    }
+
+    /// Characters in a document header's JSON other than the document ID and segment ID.
+    const HEADER_JSON_OVERHEAD: usize = r#"{"_did_":"","_sid_":}"#.len();
+    const HEADER_PREFIX_LENGTH: usize = 3;
+    const DOCUMENT_ID_MAX_LENGTH: usize = 100;
+    const AES_IV_LENGTH: usize = 12;
+    const AES_TAG_LENGTH: usize = 16;
+
+    /// Large enough for any document ID; the exact size depends on the ID.
+    pub fn document_encrypted_size(sdk: &IronOxide, plaintext_length: usize) -> usize {
+        HEADER_PREFIX_LENGTH
+            + HEADER_JSON_OVERHEAD
+            + DOCUMENT_ID_MAX_LENGTH
+            + sdk.device().segment_id().to_string().len()
+            + AES_IV_LENGTH
+            + plaintext_length
+            + AES_TAG_LENGTH
+    }
+
+    /// IronOxide takes the plaintext by value. The first attempt uses the copy made before
+    /// `retry`; only a retry copies the buffer again.
+    fn take_or_copy(copy: &mut Option<Vec<u8>>, plaintext: &[u8]) -> Vec<u8> {
+        copy.take().unwrap_or_else(|| plaintext.to_vec())
+    }
+
+    pub fn document_decrypted_size(encrypted_data: DirectByteBuffer) -> Result<usize, Error> {
+        decrypted_size(&encrypted_data)
+    }
+
+    fn decrypted_size(encrypted_data: &DirectByteBuffer) -> Result<usize, Error> {
+        let bytes = encrypted_data.as_slice()?;
+        let header = document_header::parse(u8_conv(bytes))?;
+        bytes
+            .len()
+            .checked_sub(document_header::length(&header) + AES_IV_LENGTH + AES_TAG_LENGTH)
+            .ok_or_else(|| Error::validation("Encrypted document is truncated."))
+    }
+
+    pub fn document_encrypt(
+        sdk: &IronOxide,
+        data: DirectByteBuffer,
+        mut out: DirectByteBuffer,
+        opts: &DocumentEncryptOpts,
+    ) -> Result<DocumentEncryptDirectResult, Error> {
+        let plaintext = data.as_slice()?;
+        // check before encrypting, so a managed document isn't created when it can't be returned
+        out.check_fits(document_encrypted_size(sdk, plaintext.len()))?;
+        let mut copy = Some(plaintext.to_vec());
+        let result = sdk.retry(false, |sdk| {
+            sdk.document_encrypt(take_or_copy(&mut copy, plaintext), opts)
+        })?;
+        Ok(DocumentEncryptDirectResult {
+            id: result.id().clone(),
+            length: out.write(result.encrypted_data())?,
+            encrypted_deks: None,
+            succeeded: result.grants().to_vec(),
+            failed: result.access_errs().to_vec(),
+        })
+    }
+
+    pub fn document_decrypt(
+        sdk: &IronOxide,
+        encrypted_data: DirectByteBuffer,
+        mut out: DirectByteBuffer,
+    ) -> Result<DocumentDecryptDirectResult, Error> {
+        let encrypted = encrypted_data.as_slice()?;
+        out.check_fits(decrypted_size(&encrypted_data)?)?;
+        let result = sdk.retry(true, |sdk| sdk.document_decrypt(encrypted))?;
+        Ok(DocumentDecryptDirectResult {
+            id: result.id().clone(),
+            length: out.write(result.decrypted_data())?,
+        })
+    }
+
+    pub fn document_encrypt_unmanaged(
+        sdk: &IronOxide,
+        data: DirectByteBuffer,
+        mut out: DirectByteBuffer,
+        opts: &DocumentEncryptOpts,
+    ) -> Result<DocumentEncryptDirectResult, Error> {
+        let plaintext = data.as_slice()?;
+        out.check_fits(document_encrypted_size(sdk, plaintext.len()))?;
+        let mut copy = Some(plaintext.to_vec());
+        let result = sdk.retry(true, |sdk| {
+            sdk.document_encrypt_unmanaged(take_or_copy(&mut copy, plaintext), opts)
+        })?;
+        Ok(DocumentEncryptDirectResult {
+            id: result.id().clone(),
+            length: out.write(result.encrypted_data())?,
+            encrypted_deks: Some(EncryptedDeks(result.encrypted_deks().to_vec())),
+            succeeded: result.grants().to_vec(),
+            failed: result.access_errs().to_vec(),
+        })
+    }
+
+    pub fn document_decrypt_unmanaged(
+        sdk: &IronOxide,
+        encrypted_data: DirectByteBuffer,
+        encrypted_deks: &[i8],
+        mut out: DirectByteBuffer,
+    ) -> Result<DocumentDecryptDirectResult, Error> {
+        let encrypted = encrypted_data.as_slice()?;
+        out.check_fits(decrypted_size(&encrypted_data)?)?;
+        let result = sdk.retry(true, |sdk| {
+            sdk.document_decrypt_unmanaged(encrypted, i8_conv(encrypted_deks))
+        })?;
+        Ok(DocumentDecryptDirectResult {
+            id: result.id().clone(),
+            length: out.write(result.decrypted_data())?,
+        })
+    }
 }
+#[cfg(any(feature = "java", feature = "android"))]
+use direct_buffer::DirectByteBuffer;
 
 fn document_decrypt_unmanaged(
     sdk: &IronOxide,
     encrypted_data: &[i8],
     encrypted_deks: &[i8],
-) -> Result<DocumentDecryptUnmanagedResult, String> {
-    Ok(sdk.document_decrypt_unmanaged(i8_conv(encrypted_data), i8_conv(encrypted_deks))?)
+) -> Result<DocumentDecryptUnmanagedResult, Error> {
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_decrypt_unmanaged(i8_conv(encrypted_data), i8_conv(encrypted_deks))
+    })?)
 }
 
 fn document_get_metadata_unmanaged(
     sdk: &IronOxide,
     edeks: &[i8],
-) -> Result<DocumentMetadataUnmanagedResult, String> {
-    Ok(sdk.document_get_metadata_unmanaged(i8_conv(edeks))?)
+) -> Result<DocumentMetadataUnmanagedResult, Error> {
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_get_metadata_unmanaged(i8_conv(edeks))
+    })?)
 }
 
 fn document_get_id_from_bytes_unmanaged(
     sdk: &IronOxide,
     encrypted_document: &[i8],
-) -> Result<DocumentId, String> {
+) -> Result<DocumentId, Error> {
     Ok(sdk.document_get_id_from_bytes_unmanaged(i8_conv(encrypted_document))?)
 }
 
 fn document_get_id_from_edeks_unmanaged(
     sdk: &IronOxide,
     edeks: &[i8],
-) -> Result<DocumentId, String> {
+) -> Result<DocumentId, Error> {
     Ok(sdk.document_get_id_from_edeks_unmanaged(i8_conv(edeks))?)
 }
 
@@ -1261,19 +5659,11 @@ fn document_grant_access_unmanaged(
     edeks: &[i8],
     grant_users: &[UserId],
     grant_groups: &[GroupId],
-) -> Result<DocumentAccessUnmanagedResult, String> {
-    let users_and_groups: Vec<UserOrGroup> = grant_users
-        .iter()
-        .cloned()
-        .map(|u| UserOrGroup::User { id: u })
-        .chain(
-            grant_groups
-                .iter()
-                .cloned()
-                .map(|g| UserOrGroup::Group { id: g }),
-        )
-        .collect();
-    Ok(sdk.document_grant_access_unmanaged(i8_conv(edeks), &users_and_groups)?)
+) -> Result<DocumentAccessUnmanagedResult, Error> {
+    let users_and_groups = users_and_groups(grant_users, grant_groups);
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_grant_access_unmanaged(i8_conv(edeks), &users_and_groups)
+    })?)
 }
 
 fn document_revoke_access_unmanaged(
@@ -1281,31 +5671,92 @@ fn document_revoke_access_unmanaged(
     edeks: &[i8],
     revoke_users: &[UserId],
     revoke_groups: &[GroupId],
-) -> Result<DocumentAccessUnmanagedResult, String> {
-    let users_and_groups: Vec<UserOrGroup> = revoke_users
-        .iter()
-        .cloned()
-        .map(|u| UserOrGroup::User { id: u })
-        .chain(
-            revoke_groups
-                .iter()
-                .cloned()
-                .map(|g| UserOrGroup::Group { id: g }),
-        )
-        .collect();
-    Ok(sdk.document_revoke_access_unmanaged(i8_conv(edeks), &users_and_groups)?)
+) -> Result<DocumentAccessUnmanagedResult, Error> {
+    let users_and_groups = users_and_groups(revoke_users, revoke_groups);
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_revoke_access_unmanaged(i8_conv(edeks), &users_and_groups)
+    })?)
+}
+
+fn document_grant_access_unmanaged_bulk(
+    sdk: &IronOxide,
+    edeks: &[EncryptedDeks],
+    grant_users: &[UserId],
+    grant_groups: &[GroupId],
+    max_concurrency: usize,
+) -> Result<DocumentBulkAccessUnmanagedResult, Error> {
+    document_access_unmanaged_bulk(
+        edeks,
+        &users_and_groups(grant_users, grant_groups),
+        max_concurrency,
+        |edeks, users_and_groups| {
+            sdk.retry(true, |sdk| {
+                sdk.document_grant_access_unmanaged(edeks, users_and_groups)
+            })
+        },
+    )
+}
+fn document_revoke_access_unmanaged_bulk(
+    sdk: &IronOxide,
+    edeks: &[EncryptedDeks],
+    revoke_users: &[UserId],
+    revoke_groups: &[GroupId],
+    max_concurrency: usize,
+) -> Result<DocumentBulkAccessUnmanagedResult, Error> {
+    document_access_unmanaged_bulk(
+        edeks,
+        &users_and_groups(revoke_users, revoke_groups),
+        max_concurrency,
+        |edeks, users_and_groups| {
+            sdk.retry(true, |sdk| {
+                sdk.document_revoke_access_unmanaged(edeks, users_and_groups)
+            })
+        },
+    )
+}
+fn document_access_unmanaged_bulk<F>(
+    edeks: &[EncryptedDeks],
+    users_and_groups: &[UserOrGroup],
+    max_concurrency: usize,
+    change: F,
+) -> Result<DocumentBulkAccessUnmanagedResult, Error>
+where
+    F: Fn(&[u8], &[UserOrGroup]) -> ironoxide::Result<DocumentAccessUnmanagedResult> + Sync,
+{
+    check_concurrency(max_concurrency)?;
+    let entries = run_bounded(edeks, max_concurrency, |edeks| {
+        match change(&edeks.0, users_and_groups) {
+            Ok(result) => DocumentBulkAccessUnmanagedEntry {
+                encrypted_deks: Some(EncryptedDeks(result.encrypted_deks().to_vec())),
+                succeeded: result.succeeded().to_vec(),
+                failed: result.failed().to_vec(),
+                error: None,
+            },
+            Err(e) => DocumentBulkAccessUnmanagedEntry {
+                encrypted_deks: None,
+                succeeded: vec![],
+                failed: document_bulk_access_result::failed_for_all(users_and_groups, &e),
+                error: Some(e.to_string()),
+            },
+        }
+    });
+    Ok(DocumentBulkAccessUnmanagedResult(entries))
 }
 
 fn initialize_with_public_keys(
     init: &DeviceContext,
     config: &IronOxideConfig,
     public_key_cache: &[i8],
-) -> Result<IronOxide, String> {
-    Ok(ironoxide::blocking::initialize_with_public_keys(
-        init,
-        config,
-        i8_conv(public_key_cache).to_vec(),
-    )?)
+) -> Result<IronOxide, Error> {
+    platform::check_initialized()?;
+    let sdk = config.retry_policy.run(true, || {
+        ironoxide::blocking::initialize_with_public_keys(
+            init,
+            &config.sdk,
+            i8_conv(public_key_cache).to_vec(),
+        )
+    })?;
+    Ok(IronOxide::new(sdk, &config.retry_policy))
 }
 
 fn initialize_with_public_keys_and_rotate(
@@ -1314,24 +5765,29 @@ fn initialize_with_public_keys_and_rotate(
     config: &IronOxideConfig,
     public_key_cache: &[i8],
     timeout: Option<&Duration>,
-) -> Result<IronOxide, String> {
-    let rotate_timeout = timeout.copied().or(config.sdk_operation_timeout);
-    Ok(
-        match ironoxide::blocking::initialize_with_public_keys_and_check_rotation(
+) -> Result<IronOxide, Error> {
+    platform::check_initialized()?;
+    let rotate_timeout = timeout.copied().or(config.sdk.sdk_operation_timeout);
+    let init_check = config.retry_policy.run(true, || {
+        ironoxide::blocking::initialize_with_public_keys_and_check_rotation(
             init,
-            config,
+            &config.sdk,
             i8_conv(public_key_cache).to_vec(),
-        )? {
-            InitAndRotationCheck::RotationNeeded(ironoxide, rotation) => {
+        )
+    })?;
+    Ok(IronOxide::new(
+        match init_check {
+            ironoxide::InitAndRotationCheck::RotationNeeded(ironoxide, rotation) => {
                 ironoxide.rotate_all(&rotation, password, rotate_timeout)?;
                 ironoxide
             }
-            InitAndRotationCheck::NoRotationNeeded(ironoxide) => ironoxide,
+            ironoxide::InitAndRotationCheck::NoRotationNeeded(ironoxide) => ironoxide,
         },
-    )
+        &config.retry_policy,
+    ))
 }
 
-fn export_public_key_cache(sdk: &IronOxide) -> Result<Vec<i8>, String> {
+fn export_public_key_cache(sdk: &IronOxide) -> Result<Vec<i8>, Error> {
     Ok(u8_conv(&sdk.export_public_key_cache()?).to_vec())
 }
 
@@ -1360,8 +5816,8 @@ mod access_edit_failure {
 fn create_blind_index(
     sdk: &IronOxide,
     group_id: &GroupId,
-) -> Result<EncryptedBlindIndexSalt, String> {
-    Ok(sdk.create_blind_index(group_id)?)
+) -> Result<EncryptedBlindIndexSalt, Error> {
+    Ok(sdk.retry(true, |sdk| sdk.create_blind_index(group_id))?)
 }
 
 mod encrypted_blind_index_salt {
@@ -1381,8 +5837,18 @@ mod encrypted_blind_index_salt {
     pub fn initialize_blind_index_search(
         ironoxide: &IronOxide,
         ebis: &EncryptedBlindIndexSalt,
-    ) -> Result<BlindIndexSearch, String> {
-        Ok(ebis.initialize_search_blocking(ironoxide)?)
+    ) -> Result<BlindIndexSearch, Error> {
+        Ok(ironoxide.retry(true, |sdk| ebis.initialize_search_blocking(sdk))?)
+    }
+    pub fn initialize_blind_index_search_with_timeout(
+        ironoxide: &IronOxide,
+        ebis: &EncryptedBlindIndexSalt,
+        timeout: Option<&Duration>,
+    ) -> Result<BlindIndexSearch, Error> {
+        let (ironoxide, ebis) = (ironoxide.clone(), ebis.clone());
+        with_timeout(timeout, move || {
+            ironoxide.retry(true, |sdk| ebis.initialize_search_blocking(sdk))
+        })
     }
 }
 
@@ -1392,7 +5858,7 @@ mod blind_index_search {
         bis: &BlindIndexSearch,
         query: &str,
         partition_id: Option<&str>,
-    ) -> Result<Vec<i32>, String> {
+    ) -> Result<Vec<i32>, Error> {
         Ok(bis
             .tokenize_query(query, partition_id)?
             .into_iter()
@@ -1403,7 +5869,7 @@ mod blind_index_search {
         bis: &BlindIndexSearch,
         query: &str,
         partition_id: Option<&str>,
-    ) -> Result<Vec<i32>, String> {
+    ) -> Result<Vec<i32>, Error> {
         Ok(bis
             .tokenize_data(query, partition_id)?
             .into_iter()
@@ -1492,16 +5958,20 @@ fn document_file_encrypt(
     source_path: &str,
     destination_path: &str,
     opts: &DocumentEncryptOpts,
-) -> Result<DocumentFileEncryptResult, String> {
-    Ok(sdk.document_file_encrypt(source_path, destination_path, opts)?)
+) -> Result<DocumentFileEncryptResult, Error> {
+    Ok(sdk.retry(false, |sdk| {
+        sdk.document_file_encrypt(source_path, destination_path, opts)
+    })?)
 }
 
 fn document_file_decrypt(
     sdk: &IronOxide,
     source_path: &str,
     destination_path: &str,
-) -> Result<DocumentFileDecryptResult, String> {
-    Ok(sdk.document_file_decrypt(source_path, destination_path)?)
+) -> Result<DocumentFileDecryptResult, Error> {
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_file_decrypt(source_path, destination_path)
+    })?)
 }
 
 fn document_file_encrypt_unmanaged(
@@ -1509,8 +5979,10 @@ fn document_file_encrypt_unmanaged(
     source_path: &str,
     destination_path: &str,
     opts: &DocumentEncryptOpts,
-) -> Result<DocumentFileEncryptUnmanagedResult, String> {
-    Ok(sdk.document_file_encrypt_unmanaged(source_path, destination_path, opts)?)
+) -> Result<DocumentFileEncryptUnmanagedResult, Error> {
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_file_encrypt_unmanaged(source_path, destination_path, opts)
+    })?)
 }
 
 fn document_file_decrypt_unmanaged(
@@ -1518,10 +5990,475 @@ fn document_file_decrypt_unmanaged(
     source_path: &str,
     destination_path: &str,
     encrypted_deks: &[i8],
-) -> Result<DocumentFileDecryptUnmanagedResult, String> {
-    Ok(sdk.document_file_decrypt_unmanaged(
-        source_path,
-        destination_path,
-        i8_conv(encrypted_deks),
-    )?)
+) -> Result<DocumentFileDecryptUnmanagedResult, Error> {
+    Ok(sdk.retry(true, |sdk| {
+        sdk.document_file_decrypt_unmanaged(source_path, destination_path, i8_conv(encrypted_deks))
+    })?)
+}
+
+// Variants of the IronOxide operations above that accept a per-call timeout. Each takes owned copies
+// of its arguments so the operation can outlive the caller if the timeout elapses.
+fn initialize_with_timeout(
+    init: &DeviceContext,
+    config: &IronOxideConfig,
+    timeout: Option<&Duration>,
+) -> Result<IronOxide, Error> {
+    platform::check_initialized()?;
+    let (init, config) = (init.clone(), config.clone());
+    let retry_policy = config.retry_policy.clone();
+    with_timeout(timeout, move || {
+        config
+            .retry_policy
+            .run(true, || ironoxide::blocking::initialize(&init, &config.sdk))
+    })
+    .map(|sdk| IronOxide::new(sdk, &retry_policy))
+}
+fn initialize_with_public_keys_with_timeout(
+    init: &DeviceContext,
+    config: &IronOxideConfig,
+    public_key_cache: &[i8],
+    timeout: Option<&Duration>,
+) -> Result<IronOxide, Error> {
+    platform::check_initialized()?;
+    let (init, config) = (init.clone(), config.clone());
+    let retry_policy = config.retry_policy.clone();
+    let public_key_cache = i8_conv(public_key_cache).to_vec();
+    with_timeout(timeout, move || {
+        config.retry_policy.run(true, || {
+            ironoxide::blocking::initialize_with_public_keys(
+                &init,
+                &config.sdk,
+                public_key_cache.clone(),
+            )
+        })
+    })
+    .map(|sdk| IronOxide::new(sdk, &retry_policy))
+}
+fn user_list_devices_with_timeout(
+    sdk: &IronOxide,
+    timeout: Option<&Duration>,
+) -> Result<UserDeviceListResult, Error> {
+    let sdk = sdk.clone();
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.user_list_devices())
+    })
+}
+fn user_get_public_key_with_timeout(
+    sdk: &IronOxide,
+    users: &[UserId],
+    timeout: Option<&Duration>,
+) -> Result<Vec<UserWithKey>, Error> {
+    let (sdk, users) = (sdk.clone(), users.to_vec());
+    let result = with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.user_get_public_key(&users))
+    })?;
+    Ok(result.into_iter().map(UserWithKey).collect())
+}
+fn document_list_with_timeout(
+    sdk: &IronOxide,
+    timeout: Option<&Duration>,
+) -> Result<DocumentListResult, Error> {
+    let sdk = sdk.clone();
+    with_timeout(timeout, move || sdk.retry(true, |sdk| sdk.document_list()))
+}
+fn document_get_metadata_with_timeout(
+    sdk: &IronOxide,
+    id: &DocumentId,
+    timeout: Option<&Duration>,
+) -> Result<DocumentMetadataResult, Error> {
+    let (sdk, id) = (sdk.clone(), id.clone());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.document_get_metadata(&id))
+    })
+}
+fn document_decrypt_with_timeout(
+    sdk: &IronOxide,
+    data: &[i8],
+    timeout: Option<&Duration>,
+) -> Result<DocumentDecryptResult, Error> {
+    let (sdk, data) = (sdk.clone(), i8_conv(data).to_vec());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.document_decrypt(&data))
+    })
+}
+fn document_update_name_with_timeout(
+    sdk: &IronOxide,
+    document_id: &DocumentId,
+    name: Option<&DocumentName>,
+    timeout: Option<&Duration>,
+) -> Result<DocumentMetadataResult, Error> {
+    let (sdk, document_id, name) = (sdk.clone(), document_id.clone(), name.cloned());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| {
+            sdk.document_update_name(&document_id, name.as_ref())
+        })
+    })
+}
+fn group_list_with_timeout(
+    sdk: &IronOxide,
+    timeout: Option<&Duration>,
+) -> Result<GroupListResult, Error> {
+    let sdk = sdk.clone();
+    with_timeout(timeout, move || sdk.retry(true, |sdk| sdk.group_list()))
+}
+fn group_get_metadata_with_timeout(
+    sdk: &IronOxide,
+    id: &GroupId,
+    timeout: Option<&Duration>,
+) -> Result<GroupGetResult, Error> {
+    let (sdk, id) = (sdk.clone(), id.clone());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.group_get_metadata(&id))
+    })
+}
+fn group_update_name_with_timeout(
+    sdk: &IronOxide,
+    id: &GroupId,
+    name: Option<&GroupName>,
+    timeout: Option<&Duration>,
+) -> Result<GroupMetaResult, Error> {
+    let (sdk, id, name) = (sdk.clone(), id.clone(), name.cloned());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.group_update_name(&id, name.as_ref()))
+    })
+}
+fn document_encrypt_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    data: &[i8],
+    opts: &DocumentEncryptOpts,
+    timeout: Option<&Duration>,
+) -> Result<DocumentEncryptUnmanagedResult, Error> {
+    let (sdk, data, opts) = (sdk.clone(), i8_conv(data).to_vec(), opts.clone());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| {
+            sdk.document_encrypt_unmanaged(data.clone(), &opts)
+        })
+    })
+}
+fn document_decrypt_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    encrypted_data: &[i8],
+    encrypted_deks: &[i8],
+    timeout: Option<&Duration>,
+) -> Result<DocumentDecryptUnmanagedResult, Error> {
+    let sdk = sdk.clone();
+    let encrypted_data = i8_conv(encrypted_data).to_vec();
+    let encrypted_deks = i8_conv(encrypted_deks).to_vec();
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| {
+            sdk.document_decrypt_unmanaged(&encrypted_data, &encrypted_deks)
+        })
+    })
+}
+fn document_grant_access_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    edeks: &[i8],
+    grant_users: &[UserId],
+    grant_groups: &[GroupId],
+    timeout: Option<&Duration>,
+) -> Result<DocumentAccessUnmanagedResult, Error> {
+    let (sdk, edeks) = (sdk.clone(), i8_conv(edeks).to_vec());
+    let users_and_groups = users_and_groups(grant_users, grant_groups);
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| {
+            sdk.document_grant_access_unmanaged(&edeks, &users_and_groups)
+        })
+    })
+}
+fn document_revoke_access_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    edeks: &[i8],
+    revoke_users: &[UserId],
+    revoke_groups: &[GroupId],
+    timeout: Option<&Duration>,
+) -> Result<DocumentAccessUnmanagedResult, Error> {
+    let (sdk, edeks) = (sdk.clone(), i8_conv(edeks).to_vec());
+    let users_and_groups = users_and_groups(revoke_users, revoke_groups);
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| {
+            sdk.document_revoke_access_unmanaged(&edeks, &users_and_groups)
+        })
+    })
+}
+fn document_get_metadata_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    edeks: &[i8],
+    timeout: Option<&Duration>,
+) -> Result<DocumentMetadataUnmanagedResult, Error> {
+    let (sdk, edeks) = (sdk.clone(), i8_conv(edeks).to_vec());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.document_get_metadata_unmanaged(&edeks))
+    })
+}
+fn create_blind_index_with_timeout(
+    sdk: &IronOxide,
+    group_id: &GroupId,
+    timeout: Option<&Duration>,
+) -> Result<EncryptedBlindIndexSalt, Error> {
+    let (sdk, group_id) = (sdk.clone(), group_id.clone());
+    with_timeout(timeout, move || {
+        sdk.retry(true, |sdk| sdk.create_blind_index(&group_id))
+    })
+}
+fn document_file_decrypt_with_timeout(
+    sdk: &IronOxide,
+    source_path: &str,
+    destination_path: &str,
+    timeout: Option<&Duration>,
+) -> Result<DocumentFileDecryptResult, Error> {
+    let sdk = sdk.clone();
+    let source_path = source_path.to_string();
+    with_file_timeout(timeout, destination_path, move |destination_path| {
+        sdk.retry(true, |sdk| {
+            sdk.document_file_decrypt(&source_path, destination_path)
+        })
+    })
+}
+fn document_file_encrypt_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    source_path: &str,
+    destination_path: &str,
+    opts: &DocumentEncryptOpts,
+    timeout: Option<&Duration>,
+) -> Result<DocumentFileEncryptUnmanagedResult, Error> {
+    let sdk = sdk.clone();
+    let source_path = source_path.to_string();
+    let opts = opts.clone();
+    with_file_timeout(timeout, destination_path, move |destination_path| {
+        sdk.retry(true, |sdk| {
+            sdk.document_file_encrypt_unmanaged(&source_path, destination_path, &opts)
+        })
+    })
+}
+fn document_file_decrypt_unmanaged_with_timeout(
+    sdk: &IronOxide,
+    source_path: &str,
+    destination_path: &str,
+    encrypted_deks: &[i8],
+    timeout: Option<&Duration>,
+) -> Result<DocumentFileDecryptUnmanagedResult, Error> {
+    let sdk = sdk.clone();
+    let source_path = source_path.to_string();
+    let encrypted_deks = i8_conv(encrypted_deks).to_vec();
+    with_file_timeout(timeout, destination_path, move |destination_path| {
+        sdk.retry(true, |sdk| {
+            sdk.document_file_decrypt_unmanaged(&source_path, destination_path, &encrypted_deks)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ironoxide::common::SdkOperation;
+
+    fn timed_out() -> IronOxideErr {
+        IronOxideErr::OperationTimedOut {
+            operation: SdkOperation::DocumentList,
+            duration: Duration::from_millis(1),
+        }
+    }
+
+    fn retrying(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Run `policy` over an operation that fails with `err` until its `succeed_on`th attempt,
+    /// returning whether it succeeded and how many attempts were made.
+    fn attempts(
+        policy: &RetryPolicy,
+        idempotent: bool,
+        succeed_on: u32,
+        err: impl Fn() -> IronOxideErr,
+    ) -> (bool, u32) {
+        let mut count = 0;
+        let result = policy.run(idempotent, || {
+            count += 1;
+            if count == succeed_on {
+                Ok(())
+            } else {
+                Err(err())
+            }
+        });
+        (result.is_ok(), count)
+    }
+
+    #[test]
+    fn run_attempts_once_by_default() {
+        assert_eq!(
+            attempts(&RetryPolicy::default(), true, u32::MAX, timed_out),
+            (false, 1)
+        );
+    }
+
+    #[test]
+    fn run_retries_transient_errors_up_to_max_attempts() {
+        assert_eq!(
+            attempts(&retrying(3), true, u32::MAX, timed_out),
+            (false, 3)
+        );
+        assert_eq!(attempts(&retrying(3), true, 2, timed_out), (true, 2));
+        assert_eq!(attempts(&retrying(3), true, 1, timed_out), (true, 1));
+    }
+
+    #[test]
+    fn run_treats_zero_max_attempts_as_one() {
+        assert_eq!(
+            attempts(&retrying(0), true, u32::MAX, timed_out),
+            (false, 1)
+        );
+    }
+
+    #[test]
+    fn run_doesnt_retry_permanent_errors() {
+        let invalid = || IronOxideErr::ValidationError("id".to_string(), "invalid".to_string());
+        assert_eq!(attempts(&retrying(3), true, u32::MAX, invalid), (false, 1));
+    }
+
+    #[test]
+    fn run_only_retries_non_idempotent_operations_when_allowed() {
+        assert_eq!(
+            attempts(&retrying(3), false, u32::MAX, timed_out),
+            (false, 1)
+        );
+        let policy = RetryPolicy {
+            idempotent_only: false,
+            ..retrying(3)
+        };
+        assert_eq!(attempts(&policy, false, u32::MAX, timed_out), (false, 3));
+    }
+
+    #[test]
+    fn is_retryable_follows_the_policy() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retryable(&timed_out()));
+        assert!(!policy.is_retryable(&IronOxideErr::MissingTransformBlocks));
+        let never = RetryPolicy {
+            retry_timeouts: false,
+            ..RetryPolicy::default()
+        };
+        assert!(!never.is_retryable(&timed_out()));
+    }
+
+    #[test]
+    fn is_retryable_status_retries_server_errors_and_rate_limiting() {
+        let policy = RetryPolicy::default();
+        for status in [500, 502, 503, 504, 429] {
+            assert!(policy.is_retryable_status(Some(status)), "{status}");
+        }
+        for status in [400, 401, 403, 404, 409] {
+            assert!(!policy.is_retryable_status(Some(status)), "{status}");
+        }
+        let no_server_errors = RetryPolicy {
+            retry_server_errors: false,
+            ..RetryPolicy::default()
+        };
+        assert!(!no_server_errors.is_retryable_status(Some(503)));
+        assert!(no_server_errors.is_retryable_status(None));
+    }
+
+    #[test]
+    fn is_retryable_status_retries_missing_responses() {
+        assert!(RetryPolicy::default().is_retryable_status(None));
+        let no_connection_errors = RetryPolicy {
+            retry_connection_errors: false,
+            ..RetryPolicy::default()
+        };
+        assert!(!no_connection_errors.is_retryable_status(None));
+        assert!(no_connection_errors.is_retryable_status(Some(503)));
+    }
+
+    #[test]
+    fn backoff_is_jittered_below_a_doubling_capped_ceiling() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            ..RetryPolicy::default()
+        };
+        for _ in 0..100 {
+            assert!(policy.backoff(1) <= Duration::from_millis(100));
+            assert!(policy.backoff(2) <= Duration::from_millis(200));
+            assert!(policy.backoff(3) <= Duration::from_millis(350));
+            assert!(policy.backoff(40) <= Duration::from_millis(350));
+        }
+        // with 100 draws, full jitter picks something other than the ceiling
+        assert!((0..100).any(|_| policy.backoff(1) < Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn backoff_is_zero_without_an_initial_backoff() {
+        assert_eq!(retrying(3).backoff(5), Duration::ZERO);
+    }
+
+    #[test]
+    fn json_round_trips_and_defaults_unset_fields() {
+        let policy = RetryPolicy {
+            idempotent_only: false,
+            ..retrying(4)
+        };
+        assert_eq!(json::from_str::<RetryPolicy>(&to_json(&policy)), Ok(policy));
+        let read: RetryPolicy =
+            json::from_str(r#"{"maxAttempts": 2, "retryTimeouts": null}"#).unwrap();
+        assert_eq!(
+            read,
+            RetryPolicy {
+                max_attempts: 2,
+                ..RetryPolicy::default()
+            }
+        );
+    }
+
+    #[test]
+    fn json_reports_the_field_that_cant_be_read() {
+        let err = json::from_str::<DocumentListFilter>(r#"{"createdAfter": "yesterday"}"#);
+        assert_eq!(
+            err,
+            Err("createdAfter: must be a whole number.".to_string())
+        );
+    }
+
+    #[test]
+    fn ffi_guard_turns_panics_into_errors() {
+        let message = |result: Result<usize, FfiError>| match result {
+            Err(FfiError::Panicked(message)) => Some(message),
+            _ => None,
+        };
+        assert_eq!(
+            message(ffi_guard::infallible(|| panic!("test panic"))),
+            Some("test panic".to_string())
+        );
+        assert_eq!(message(ffi_guard::infallible(|| 1)), None);
+        assert_eq!(
+            ffi_guard::result::<(), _>(|| Err("failed".to_string()))
+                .err()
+                .map(FfiError::into_message),
+            Some("failed".to_string())
+        );
+        assert_eq!(
+            ffi_guard::infallible::<()>(|| panic!("test panic"))
+                .err()
+                .map(FfiError::into_message),
+            Some("IronOxide internal error: test panic".to_string())
+        );
+    }
+
+    #[test]
+    fn errors_keep_their_kind() {
+        let invalid = IronOxideErr::ValidationError("id".to_string(), "invalid".to_string());
+        assert_eq!(Error::from(invalid).kind(), ErrorKind::Validation);
+        assert_eq!(
+            Error::from(IronOxideErr::MissingTransformBlocks).kind(),
+            ErrorKind::Other
+        );
+        assert_eq!(Error::from(timed_out()).kind(), ErrorKind::Timeout);
+        assert_eq!(
+            check_concurrency(0).map_err(|e| e.kind()),
+            Err(ErrorKind::Validation)
+        );
+    }
 }
@@ -0,0 +1,81 @@
+#pragma once
+
+#include <optional>
+#include <stdexcept>
+#include <string>
+#include <utility>
+#include <variant>
+
+#include "ironoxide_error.h"
+
+namespace sdk {
+
+/// Thrown by IronOxide operations that fail, when built with the `cpp-exceptions` feature. Each
+/// `ErrorKind` is thrown as its own subclass, so callers can catch just the failures they handle.
+class IronOxideError : public std::runtime_error {
+public:
+    IronOxideError(ErrorKind kind, const std::string &message) : std::runtime_error(message), kind_(kind) {}
+    ErrorKind kind() const noexcept { return kind_; }
+
+private:
+    ErrorKind kind_;
+};
+
+/// Thrown when an operation didn't finish within the SDK-wide or per-call timeout.
+class IronOxideTimeoutError : public IronOxideError {
+public:
+    explicit IronOxideTimeoutError(const std::string &message) : IronOxideError(ErrorKind::Timeout, message) {}
+};
+
+/// Thrown when a request to the IronCore webservice failed or was rejected.
+class IronOxideRequestError : public IronOxideError {
+public:
+    explicit IronOxideRequestError(const std::string &message) : IronOxideError(ErrorKind::Request, message) {}
+};
+
+/// Thrown when an argument wasn't valid.
+class IronOxideValidationError : public IronOxideError {
+public:
+    explicit IronOxideValidationError(const std::string &message)
+        : IronOxideError(ErrorKind::Validation, message) {}
+};
+
+/// Thrown when IronOxide hit an unexpected internal error, such as a panic. The SDK should not
+/// be used after this.
+class IronOxideInternalError : public IronOxideError {
+public:
+    explicit IronOxideInternalError(const std::string &message) : IronOxideError(ErrorKind::Internal, message) {}
+};
+
+[[noreturn]] inline void throw_ironoxide_error(const ErrorInfo &error)
+{
+    switch (error.kind()) {
+    case ErrorKind::Timeout:
+        throw IronOxideTimeoutError(error.message());
+    case ErrorKind::Request:
+        throw IronOxideRequestError(error.message());
+    case ErrorKind::Validation:
+        throw IronOxideValidationError(error.message());
+    case ErrorKind::Internal:
+        throw IronOxideInternalError(error.message());
+    default:
+        throw IronOxideError(error.kind(), error.message());
+    }
+}
+
+template <class T> T throw_on_error(std::variant<T, ErrorInfo> result)
+{
+    if (auto *error = std::get_if<ErrorInfo>(&result)) {
+        throw_ironoxide_error(*error);
+    }
+    return std::get<T>(std::move(result));
+}
+
+inline void throw_on_error(std::optional<ErrorInfo> error)
+{
+    if (error) {
+        throw_ironoxide_error(*error);
+    }
+}
+
+} // namespace sdk
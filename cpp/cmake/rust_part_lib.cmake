@@ -4,6 +4,11 @@ set(RUSTC "rustc")
 get_filename_component(ROOT_DIR "${CMAKE_CURRENT_SOURCE_DIR}../" DIRECTORY)
 set(RUST_BUILD_CWD "${ROOT_DIR}/cpp/")
 
+option(IRONOXIDE_CPP_EXCEPTIONS "Throw IronOxideError instead of returning std::variant" OFF)
+if (IRONOXIDE_CPP_EXCEPTIONS)
+  set(CARGO_ADDON_ARGS "--features=cpp-exceptions")
+endif ()
+
 function (find_cargo_target_directory CACHEVAR)
   if (DEFINED ${CACHEVAR})
     message(STATUS "Cashed target path: ${${CACHEVAR}}")
@@ -64,9 +69,9 @@ include(${CMAKE_BINARY_DIR}/ironoxide_regen_headers.cmake)
 find_cargo_target_directory(TARGET_PATH)
 
 if (NOT RUST_DEBUG_BUILD)
-  set(CARGO_BUILD ${CARGO_CMD} "build" "${RUST_TARGET}" "--release")
+  set(CARGO_BUILD ${CARGO_CMD} "build" "${RUST_TARGET}" "--release" ${CARGO_ADDON_ARGS})
 else()
-  set(CARGO_BUILD ${CARGO_CMD} "build" "${RUST_TARGET}")
+  set(CARGO_BUILD ${CARGO_CMD} "build" "${RUST_TARGET}" ${CARGO_ADDON_ARGS})
 endif()
 
 if (WIN32)
@@ -5,3 +5,180 @@ foreign_typemap!(
         $out = $p.unix_timestamp() * 1000 + $p.millisecond() as i64;
     };
 );
+
+// `&[i8]` arguments take a `ByteView`, which converts from the usual C++ ways of holding bytes
+// without copying them. `RustSlice<const int8_t>` is still accepted so existing code keeps working.
+foreign_typemap!(
+    define_c_type!(module = "ironoxide_bytes.h";
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct CByteView {
+            data: *const u8,
+            len: usize,
+        }
+
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct CByteVec {
+            data: *mut u8,
+            len: usize,
+            capacity: usize,
+        }
+    );
+    foreign_code!(module = "ironoxide_bytes.h";
+                    r##"
+#ifdef __cplusplus
+
+#include <cstddef>
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <vector>
+#if __cplusplus >= 202002L
+#include <span>
+#endif
+#include "rust_slice_tmpl.hpp"
+
+// defined in lib.rs
+extern "C" void CByteVec_free(CByteVec v);
+
+namespace $RUST_SWIG_USER_NAMESPACE {
+
+/// Bytes passed to IronOxide. Only refers to the caller's bytes, which must outlive the call.
+class ByteView final {
+public:
+    ByteView(const uint8_t *data, size_t len) noexcept : data_(data), len_(len) {}
+    ByteView(std::string_view s) noexcept : ByteView(reinterpret_cast<const uint8_t *>(s.data()), s.size()) {}
+    ByteView(const std::string &s) noexcept : ByteView(std::string_view(s)) {}
+    ByteView(const std::vector<uint8_t> &v) noexcept : ByteView(v.data(), v.size()) {}
+    ByteView(const RustSlice<const int8_t> &s) noexcept
+        : ByteView(reinterpret_cast<const uint8_t *>(s.begin()), s.size()) {}
+#if __cplusplus >= 202002L
+    ByteView(std::span<const std::byte> s) noexcept : ByteView(reinterpret_cast<const uint8_t *>(s.data()), s.size()) {}
+    ByteView(std::span<const uint8_t> s) noexcept : ByteView(s.data(), s.size()) {}
+#endif
+    CByteView as_c() const noexcept { return CByteView{data_, len_}; }
+
+private:
+    const uint8_t *data_;
+    size_t len_;
+};
+
+/// Copy bytes returned by IronOxide into a vector and free the Rust allocation.
+inline std::vector<uint8_t> byte_vec_from_rust(CByteVec v)
+{
+    struct Free {
+        CByteVec v;
+        ~Free() { CByteVec_free(v); }
+    } free_on_exit{v};
+    return std::vector<uint8_t>(v.data, v.data + v.len);
+}
+
+} // namespace $RUST_SWIG_USER_NAMESPACE
+#endif
+"##);
+    ($p:r_type) &[i8] <= CByteView {
+        assert!($p.len == 0 || !$p.data.is_null());
+        $out = if $p.len == 0 {
+            &[]
+        } else {
+            unsafe { ::std::slice::from_raw_parts($p.data as *const i8, $p.len) }
+        };
+    };
+    ($p:f_type, req_modules = ["\"ironoxide_bytes.h\""]) <= "ByteView" "$p.as_c()";
+
+    ($p:r_type) Vec<i8> => CByteVec {
+        let mut tmp = ::std::mem::ManuallyDrop::new($p);
+        $out = CByteVec {
+            data: tmp.as_mut_ptr() as *mut u8,
+            len: tmp.len(),
+            capacity: tmp.capacity(),
+        };
+    };
+    ($p:f_type, req_modules = ["\"ironoxide_bytes.h\"", "<vector>"]) => "std::vector<uint8_t>"
+        "byte_vec_from_rust($p)";
+);
+
+// everything declared in lib.rs.in is called through `ffi_guarded`, which returns `Result<_, FfiError>`;
+// see `ffi_guard` in lib.rs. The error reaches C++ as an `ErrorInfo`, which has the kind of failure as well
+// as the message.
+foreign_typemap!(
+    define_c_type!(module = "ironoxide_error.h";
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct CIronOxideError {
+            kind: u32,
+            message: *mut u8,
+            len: usize,
+            capacity: usize,
+        }
+    );
+    foreign_code!(module = "ironoxide_error.h";
+                    r##"
+#ifdef __cplusplus
+
+#include <cstdint>
+#include <string>
+#include <string_view>
+#include <utility>
+
+// defined in lib.rs
+extern "C" void CIronOxideError_free(CIronOxideError e);
+
+namespace $RUST_SWIG_USER_NAMESPACE {
+
+/// Why an IronOxide operation failed.
+enum class ErrorKind : uint32_t {
+    /// Anything else, such as failing to decrypt or to write a file.
+    Other = 0,
+    /// The operation didn't finish within the SDK-wide or per-call timeout.
+    Timeout = 1,
+    /// The request to the IronCore webservice failed or was rejected.
+    Request = 2,
+    /// An argument wasn't valid.
+    Validation = 3,
+    /// IronOxide hit an unexpected internal error, such as a panic. The SDK should not be used after this.
+    Internal = 4,
+};
+
+/// Error returned by an IronOxide operation that failed.
+class ErrorInfo final {
+public:
+    ErrorInfo(ErrorKind kind, std::string message) : kind_(kind), message_(std::move(message)) {}
+    ErrorKind kind() const noexcept { return kind_; }
+    const std::string &message() const noexcept { return message_; }
+    // the same as `message()`, under the names `RustString` has
+    std::string to_std_string() const { return message_; }
+    std::string_view to_string_view() const noexcept { return message_; }
+
+private:
+    ErrorKind kind_;
+    std::string message_;
+};
+
+/// Copy an error returned by IronOxide and free the Rust allocation.
+inline ErrorInfo error_from_rust(CIronOxideError e)
+{
+    struct Free {
+        CIronOxideError e;
+        ~Free() { CIronOxideError_free(e); }
+    } free_on_exit{e};
+    return ErrorInfo(static_cast<ErrorKind>(e.kind), std::string(reinterpret_cast<const char *>(e.message), e.len));
+}
+
+} // namespace $RUST_SWIG_USER_NAMESPACE
+#endif
+"##);
+    ($p:r_type) FfiError => CIronOxideError {
+        let kind = $p.kind_code();
+        let mut message = ::std::mem::ManuallyDrop::new($p.into_message().into_bytes());
+        $out = CIronOxideError {
+            kind,
+            message: message.as_mut_ptr(),
+            len: message.len(),
+            capacity: message.capacity(),
+        };
+    };
+    ($p:f_type, req_modules = ["\"ironoxide_error.h\"", "<string>"]) => "ErrorInfo"
+        "error_from_rust($p)";
+);
@@ -84,6 +84,230 @@ class CommonTests extends TestSuite {
       val maybeSdk = Try(IronOxide.initialize(primaryUserDevice, shortConfig))
       maybeSdk.isFailure shouldBe true
     }
+    "fail with short timeout after retrying" in {
+      val retryPolicy =
+        new RetryPolicy(3, Duration.fromMillis(10), Duration.fromMillis(50), true, true, true, true)
+      val shortConfig = new IronOxideConfig(new PolicyCachingConfig, Duration.fromMillis(5), retryPolicy)
+      val maybeSdk = Try(IronOxide.initialize(primaryUserDevice, shortConfig))
+      maybeSdk.isFailure shouldBe true
+    }
+    "succeed with a retry policy" in {
+      val retryPolicy =
+        new RetryPolicy(3, Duration.fromMillis(10), Duration.fromMillis(50), true, true, true, true)
+      val config = new IronOxideConfig(new PolicyCachingConfig, Duration.fromSecs(30), retryPolicy)
+      val sdk = Try(IronOxide.initialize(primaryUserDevice, config)).toEither.value
+      Try(sdk.documentList).toEither.value.getResult should not be null
+    }
+  }
+
+  "IronOxideInternalError" should {
+    "be unchecked so it can be thrown from any method" in {
+      classOf[RuntimeException].isAssignableFrom(classOf[IronOxideInternalError]) shouldBe true
+    }
+    "be thrown when native code panics" in {
+      val thrown = Try(IronOxide.panicForTest("test panic")).failed.get
+      thrown shouldBe an[IronOxideInternalError]
+      thrown.getMessage shouldBe "IronOxide internal error: test panic"
+      // the JVM is still usable afterwards
+      UserId.validate("after-panic").getId shouldBe "after-panic"
+    }
+  }
+
+  "RetryPolicy" should {
+    "never retry by default" in {
+      val config = new IronOxideConfig
+      config.getRetryPolicy.getMaxAttempts shouldBe 1
+      config.getRetryPolicy shouldBe new RetryPolicy
+    }
+  }
+
+  "toString" should {
+    "describe what an object holds" in {
+      UserId.validate("to-string-user").toString should include("to-string-user")
+      new GroupCreateOpts().toString should include("add_as_admin: true")
+    }
+    "redact keys and decrypted data" in {
+      val deviceContext = primaryUserDevice
+      val described = deviceContext.toString
+      described should include(deviceContext.getAccountId.getId)
+      described should include("<redacted>")
+      val privateKey = java.util.Base64.getEncoder.encodeToString(deviceContext.getDevicePrivateKey.asBytes)
+      described should not include privateKey
+      deviceContext.getDevicePrivateKey.toString shouldBe "PrivateKey(<redacted>)"
+      deviceContext.getSigningPrivateKey.toString shouldBe "DeviceSigningKeyPair(<redacted>)"
+
+      val sdk = IronOxide.initialize(deviceContext, new IronOxideConfig)
+      sdk.toString should include(deviceContext.getAccountId.getId)
+      val encrypted = sdk.documentEncrypt("a secret message".getBytes, new DocumentEncryptOpts)
+      val decrypted = sdk.documentDecrypt(encrypted.getEncryptedData)
+      decrypted.toString should include("<16 bytes redacted>")
+      decrypted.toString should not include "a secret message"
+      decrypted.toString should not include "97, 32, 115"
+    }
+  }
+
+  "Option builders" should {
+    "match the defaults when nothing is set" in {
+      DocumentEncryptOpts.builder.build shouldBe new DocumentEncryptOpts
+      GroupCreateOpts.builder.build shouldBe new GroupCreateOpts
+      UserCreateOpts.builder.build shouldBe new UserCreateOpts
+      DeviceCreateOpts.builder.build shouldBe new DeviceCreateOpts
+      IronOxideConfig.builder.build shouldBe new IronOxideConfig
+    }
+    "match the positional constructors" in {
+      val user = UserId.validate("builder-user")
+      val group = GroupId.validate("builder-group")
+      val docName = DocumentName.validate("builder-doc")
+      val docOpts = DocumentEncryptOpts.builder
+        .withName(docName)
+        .withGrantToAuthor(false)
+        .withUserGrants(Array(user))
+        .withGroupGrants(Array(group))
+        .build
+      docOpts shouldBe new DocumentEncryptOpts(null, docName, false, Array(user), Array(group), null)
+      docOpts.getId shouldBe null
+      docOpts.getName shouldBe docName
+      docOpts.isGrantToAuthor shouldBe false
+      docOpts.getUserGrants shouldBe Array(user)
+      docOpts.getGroupGrants shouldBe Array(group)
+      docOpts.getPolicyGrant shouldBe null
+
+      val groupOpts = GroupCreateOpts.builder.withId(group).withOwner(user).withNeedsRotation(true).build
+      groupOpts shouldBe new GroupCreateOpts(group, null, true, true, user, Array(), Array(), true)
+      groupOpts.getId shouldBe group
+      groupOpts.getOwner shouldBe user
+      groupOpts.isAddAsAdmin shouldBe true
+      groupOpts.isAddAsMember shouldBe true
+      groupOpts.getAdmins shouldBe empty
+      groupOpts.getNeedsRotation shouldBe true
+
+      UserCreateOpts.builder.withNeedsRotation(true).build.getNeedsRotation shouldBe true
+      val deviceName = DeviceName.validate("builder-device")
+      DeviceCreateOpts.builder.withName(deviceName).build.getName shouldBe deviceName
+
+      val config = IronOxideConfig.builder.withSdkOperationTimeout(null).build
+      config.getSdkOperationTimeout shouldBe null
+      config.getRetryPolicy shouldBe new RetryPolicy
+    }
+    "not change the builder they were called on" in {
+      val builder = DocumentEncryptOpts.builder
+      builder.withGrantToAuthor(false)
+      builder.build.isGrantToAuthor shouldBe true
+    }
+    "fail to build options that can't work" in {
+      Try(DocumentEncryptOpts.builder.withGrantToAuthor(false).build).toEither.leftValue.getMessage should include(
+        "must grant access"
+      )
+      Try(GroupCreateOpts.builder.withAddAsAdmin(false).build).toEither.leftValue.getMessage should include(
+        "must have an admin"
+      )
+      val zeroTimeout = IronOxideConfig.builder.withSdkOperationTimeout(Duration.fromMillis(0))
+      Try(zeroTimeout.build).toEither.leftValue.getMessage should include("greater than zero")
+    }
+  }
+
+  "JSON" should {
+    "round-trip ids, options and policy grants" in {
+      val user = UserId.validate("json-user")
+      user.toJson shouldBe "\"json-user\""
+      UserId.fromJson(user.toJson) shouldBe user
+      val policy = new PolicyGrant(Category.validate("PII"), null, null, user)
+      PolicyGrant.fromJson(policy.toJson) shouldBe policy
+      val docOpts = DocumentEncryptOpts.builder
+        .withName(DocumentName.validate("json-doc"))
+        .withUserGrants(Array(user))
+        .withPolicyGrant(policy)
+        .build
+      DocumentEncryptOpts.fromJson(docOpts.toJson) shouldBe docOpts
+      val groupOpts = GroupCreateOpts.builder.withOwner(user).withNeedsRotation(true).build
+      GroupCreateOpts.fromJson(groupOpts.toJson) shouldBe groupOpts
+      val retryPolicy = new RetryPolicy(3, Duration.fromMillis(10), Duration.fromMillis(50), true, false, true, true)
+      val config = IronOxideConfig.builder.withRetryPolicy(retryPolicy).build
+      config.toJson should include(""""initialBackoffMillis":10""")
+      IronOxideConfig.fromJson(config.toJson) shouldBe config
+    }
+    "use the defaults for missing fields" in {
+      DocumentEncryptOpts.fromJson("{}") shouldBe new DocumentEncryptOpts
+      GroupCreateOpts.fromJson("""{"unknownField":1}""") shouldBe new GroupCreateOpts
+      IronOxideConfig.fromJson("{}") shouldBe new IronOxideConfig
+    }
+    "reject JSON that doesn't match the schema" in {
+      Try(UserId.fromJson("42")).toEither.leftValue.getMessage should include("must be a string")
+      Try(DocumentEncryptOpts.fromJson("""{"userGrants":"json-user"}""")).toEither.leftValue.getMessage should include(
+        "userGrants"
+      )
+      Try(UserId.fromJson("not json")).toEither.leftValue.getMessage should include("Invalid JSON")
+    }
+    "write results with the documented fields" in {
+      val sdk = IronOxide.initialize(primaryUserDevice, new IronOxideConfig)
+      val encrypted = sdk.documentEncrypt(Array(1.toByte), new DocumentEncryptOpts)
+      val json = encrypted.toJson
+      json should include(s""""id":"${encrypted.getId.getId}"""")
+      json should include(s""""created":${encrypted.getCreated.getTime}""")
+      json should include(java.util.Base64.getEncoder.encodeToString(encrypted.getEncryptedData))
+      json should include(s""""users":["${primaryUserDevice.getAccountId.getId}"]""")
+    }
+  }
+
+  "Serializable" should {
+    def roundTrip[A](value: A): A = {
+      val bytes = new java.io.ByteArrayOutputStream
+      val out = new java.io.ObjectOutputStream(bytes)
+      out.writeObject(value)
+      out.close()
+      new java.io.ObjectInputStream(new java.io.ByteArrayInputStream(bytes.toByteArray)).readObject.asInstanceOf[A]
+    }
+
+    "round-trip ids, options and policy grants" in {
+      val user = UserId.validate("serializable-user")
+      roundTrip(user) shouldBe user
+      roundTrip(DocumentName.validate("serializable-doc")) shouldBe DocumentName.validate("serializable-doc")
+      roundTrip(DeviceId.validate(42)) shouldBe DeviceId.validate(42)
+      val policy = new PolicyGrant(Category.validate("PII"), null, null, user)
+      roundTrip(policy) shouldBe policy
+      val docOpts = DocumentEncryptOpts.builder
+        .withName(DocumentName.validate("serializable-doc"))
+        .withGrantToAuthor(false)
+        .withUserGrants(Array(user))
+        .withPolicyGrant(policy)
+        .build
+      roundTrip(docOpts) shouldBe docOpts
+      val groupOpts = GroupCreateOpts.builder.withOwner(user).withAdmins(Array(user)).withNeedsRotation(true).build
+      roundTrip(groupOpts) shouldBe groupOpts
+      roundTrip(new UserCreateOpts(true)) shouldBe new UserCreateOpts(true)
+      roundTrip(new DeviceCreateOpts) shouldBe new DeviceCreateOpts
+      val accountId = primaryUserDevice.getAccountId
+      roundTrip(Array(accountId, user)).toList shouldBe List(accountId, user)
+    }
+    "round-trip results built by the bindings" in {
+      val groupCreate = Try(primarySdk.groupCreate(new GroupCreateOpts)).toEither.value
+      val opts = new DocumentEncryptOpts(null, null, true, Array(secondaryUser), Array(groupCreate.getId), null)
+      val unmanaged = Try(primarySdk.documentEncryptUnmanaged(Array(1, 2, 3).map(_.toByte), opts)).toEither.value
+      val header = Try(DocumentHeader.parse(unmanaged.getEncryptedData)).toEither.value
+      roundTrip(header).toJson shouldBe header.toJson
+      val info = Try(EdekInfo.parse(unmanaged.getEncryptedDeks)).toEither.value
+      roundTrip(info) shouldBe info
+      roundTrip(info.getRecipients.head) shouldBe info.getRecipients.head
+
+      val managed = Try(primarySdk.documentEncrypt(Array(4).map(_.toByte), opts)).toEither.value
+      val report = Try(primarySdk.documentExplainAccess(managed.getId)).toEither.value
+      roundTrip(report) shouldBe report
+      roundTrip(report.getGroups.head) shouldBe report.getGroups.head
+
+      val sync = Try(
+        primarySdk.groupSyncMembership(groupCreate.getId, Array(primaryUser), Array(secondaryUser), true)
+      ).toEither.value
+      roundTrip(sync) shouldBe sync
+      roundTrip(sync.getChanges.head) shouldBe sync.getChanges.head
+    }
+    "keep the native object out of the stream" in {
+      val user = UserId.validate("serializable-user")
+      roundTrip(user) should not be theSameInstanceAs(user)
+      classOf[java.io.Serializable].isAssignableFrom(classOf[UserId]) shouldBe true
+      classOf[java.io.Serializable].isAssignableFrom(classOf[DocumentEncryptResult]) shouldBe false
+      classOf[java.io.Serializable].isAssignableFrom(classOf[DeviceDeleteReport]) shouldBe false
+      classOf[java.io.Serializable].isAssignableFrom(classOf[DocumentListPage]) shouldBe false
+    }
   }
 
 }
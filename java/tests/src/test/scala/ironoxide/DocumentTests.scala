@@ -2,7 +2,8 @@ package ironoxide
 
 import scala.util.Try
 import com.ironcorelabs.sdk._
-import java.util.Date
+import java.util.{Date, OptionalLong, UUID}
+import scala.collection.JavaConverters._
 
 class DocumentTests extends TestSuite {
   "Document List" should {
@@ -12,6 +13,101 @@ class DocumentTests extends TestSuite {
       val listResult = Try(primarySdk.documentList()).toEither.value.getResult
       listResult.length should be > 0
     }
+    "succeed with a per-call timeout" in {
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), new DocumentEncryptOpts)).toEither.value
+      val listResult = Try(primarySdk.documentList(Duration.fromSecs(30))).toEither.value.getResult
+      listResult.map(_.getId) should contain(encryptResult.getId)
+    }
+    "fail with short per-call timeout" in {
+      val resp = Try(primarySdk.documentList(Duration.fromMillis(1))).toEither
+      resp.leftValue.getMessage should include("timed out")
+    }
+  }
+
+  "Document List Page" should {
+    "page through the primary user's documents" in {
+      val bytes = Array(1, 2).map(_.toByte)
+      (1 to 3).foreach(_ => Try(primarySdk.documentEncrypt(bytes, new DocumentEncryptOpts)).toEither.value)
+      val firstPage = Try(primarySdk.documentListPage(new DocumentListFilter, 2)).toEither.value
+      firstPage.getResult.length shouldBe 2
+      val secondPage = firstPage.nextPage.get
+      secondPage.getResult.length should be > 0
+      secondPage.getResult.map(_.getId) should contain noElementsOf firstPage.getResult.map(_.getId)
+    }
+    "page through every document from one fetch with nextPage" in {
+      val bytes = Array(1, 2).map(_.toByte)
+      val encryptedIds =
+        (1 to 3).map(_ => Try(primarySdk.documentEncrypt(bytes, new DocumentEncryptOpts)).toEither.value.getId)
+      val firstPage = Try(primarySdk.documentListPage(new DocumentListFilter, 2)).toEither.value
+      val pages = Iterator
+        .iterate(Option(firstPage))(_.flatMap(page => Option(page.nextPage.orElse(null))))
+        .takeWhile(_.isDefined)
+        .flatten
+        .toList
+      pages.init.foreach(_.getResult.length shouldBe 2)
+      pages.last.nextPage.isPresent shouldBe false
+      val pagedIds = pages.flatMap(_.getResult.map(_.getId))
+      pagedIds.distinct shouldBe pagedIds
+      pagedIds should contain allElementsOf encryptedIds
+    }
+    "filter by name prefix" in {
+      val prefix = UUID.randomUUID.toString
+      val opts = new DocumentEncryptOpts(null, DocumentName.validate(s"$prefix-doc"), true, Array(), Array(), null)
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), opts)).toEither.value
+      val filter = new DocumentListFilter(
+        true,
+        true,
+        true,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        prefix
+      )
+      val page = Try(primarySdk.documentListPage(filter, 10)).toEither.value
+      page.getResult.map(_.getId) shouldBe Array(encryptResult.getId)
+      page.nextPage.isPresent shouldBe false
+    }
+    "filter by creation time" in {
+      val filter = new DocumentListFilter(
+        true,
+        true,
+        true,
+        OptionalLong.of(new Date().getTime + 60000),
+        OptionalLong.empty,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        null
+      )
+      val page = Try(primarySdk.documentListPage(filter, 10)).toEither.value
+      page.getResult shouldBe empty
+    }
+    "fail for a page size of zero" in {
+      val resp = Try(primarySdk.documentListPage(new DocumentListFilter, 0)).toEither
+      resp.leftValue.getMessage should include("pageSize")
+    }
+  }
+
+  "Document List Cursor" should {
+    "iterate over the filtered documents" in {
+      val filter = new DocumentListFilter(
+        true,
+        false,
+        false,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        OptionalLong.empty,
+        null
+      )
+      val cursor = Try(primarySdk.documentListCursor(filter)).toEither.value
+      val expected = cursor.remaining
+      val documents = cursor.iterator.asScala.toList
+      documents.length shouldBe expected
+      documents.length should be > 0
+      documents.forall(_.getAssociationType == AssociationType.Owner) shouldBe true
+      cursor.remaining shouldBe 0
+    }
   }
 
   "Document Encrypt/Decrypt" should {
@@ -140,6 +236,72 @@ class DocumentTests extends TestSuite {
     }
   }
 
+  "Document Bulk Grant and Revoke" should {
+    "grant and revoke access to many documents" in {
+      val ids = (1 to 3).map(_ =>
+        Try(primarySdk.documentEncrypt(Array(1.toByte), new DocumentEncryptOpts)).toEither.value.getId
+      ).toArray
+      val grantResult =
+        Try(primarySdk.documentGrantAccessBulk(ids, Array(secondaryUser), Array(), 2)).toEither.value
+      grantResult.hasFailures shouldBe false
+      grantResult.getResult.map(_.getId) shouldBe ids
+      grantResult.getResult.foreach(_.getChanged.getUsers shouldBe Array(secondaryUser))
+      val revokeResult =
+        Try(primarySdk.documentRevokeAccessBulk(ids, Array(secondaryUser), Array(), 2)).toEither.value
+      revokeResult.hasFailures shouldBe false
+      ids.foreach { id =>
+        Try(primarySdk.documentGetMetadata(id)).toEither.value.getVisibleToUsers.length shouldBe 1
+      }
+    }
+    "report a missing document as failed for everyone" in {
+      val missing = DocumentId.validate(java.util.UUID.randomUUID.toString)
+      val result = Try(primarySdk.documentGrantAccessBulk(Array(missing), Array(secondaryUser), Array(), 1)).toEither.value
+      result.hasFailures shouldBe true
+      result.getResult.head.getChanged.getUsers shouldBe empty
+      result.getResult.head.getErrors.getUsers.map(_.getId) shouldBe Array(secondaryUser)
+      result.getResult.head.getError.isPresent shouldBe true
+    }
+    "report a failed document even with no users or groups" in {
+      val missing = DocumentId.validate(java.util.UUID.randomUUID.toString)
+      val result = Try(primarySdk.documentRevokeAccessBulk(Array(missing), Array(), Array(), 1)).toEither.value
+      result.hasFailures shouldBe true
+      result.getResult.head.getError.isPresent shouldBe true
+      val garbage = new EncryptedDeks(Array(1, 2, 3).map(_.toByte))
+      val unmanaged = Try(primarySdk.documentRevokeAccessUnmanagedBulk(Array(garbage), Array(), Array(), 1)).toEither.value
+      unmanaged.hasFailures shouldBe true
+      unmanaged.getResult.head.getError.isPresent shouldBe true
+      unmanaged.toJson should include(""""error":"""")
+    }
+    "grant and revoke access to many unmanaged documents" in {
+      val bytes = Array(1, 2, 3).map(_.toByte)
+      val encryptResults = (1 to 3).map(_ =>
+        Try(primarySdk.documentEncryptUnmanaged(bytes, new DocumentEncryptOpts)).toEither.value
+      )
+      val edeks = encryptResults.map(r => new EncryptedDeks(r.getEncryptedDeks)).toArray
+      val grantResult =
+        Try(primarySdk.documentGrantAccessUnmanagedBulk(edeks, Array(secondaryUser), Array(), 2)).toEither.value
+      grantResult.hasFailures shouldBe false
+      encryptResults.zip(grantResult.getResult).foreach { case (encrypted, granted) =>
+        val decrypted = Try(
+          secondarySdk.documentDecryptUnmanaged(encrypted.getEncryptedData, granted.getEncryptedDeks.get.getBytes)
+        ).toEither.value
+        decrypted.getDecryptedData shouldBe bytes
+      }
+      val grantedEdeks = grantResult.getResult.map(_.getEncryptedDeks.get)
+      val revokeResult =
+        Try(primarySdk.documentRevokeAccessUnmanagedBulk(grantedEdeks, Array(secondaryUser), Array(), 2)).toEither.value
+      revokeResult.hasFailures shouldBe false
+      revokeResult.getResult.foreach(_.getChanged.getUsers shouldBe Array(secondaryUser))
+    }
+    "report invalid EDEKs as failed" in {
+      val garbage = new EncryptedDeks(Array(1, 2, 3).map(_.toByte))
+      val result =
+        Try(primarySdk.documentRevokeAccessUnmanagedBulk(Array(garbage), Array(secondaryUser), Array(), 1)).toEither.value
+      result.hasFailures shouldBe true
+      result.getResult.head.getEncryptedDeks.isPresent shouldBe false
+    }
+  }
+
   "Document Encrypt/Decrypt Unmanaged" should {
     "roundtrip to self" in {
       val bytes = Array(1, 2, 3).map(_.toByte)
@@ -174,6 +336,98 @@ class DocumentTests extends TestSuite {
     }
   }
 
+  "Document Managed/Unmanaged Conversion" should {
+    "keep the id and grants in both directions" in {
+      val bytes = Array(7, 8, 9).map(_.toByte)
+      val opts = new DocumentEncryptOpts(null, null, true, Array(secondaryUser), Array(), null)
+      val unmanaged = Try(primarySdk.documentEncryptUnmanaged(bytes, opts)).toEither.value
+      val managed = Try(
+        primarySdk.documentUnmanagedToManaged(unmanaged.getEncryptedData, unmanaged.getEncryptedDeks)
+      ).toEither.value
+      managed.getId shouldBe unmanaged.getId
+      Try(secondarySdk.documentDecrypt(managed.getEncryptedData)).toEither.value.getDecryptedData shouldBe bytes
+
+      val converted = Try(primarySdk.documentManagedToUnmanaged(managed.getEncryptedData)).toEither.value
+      converted.getId shouldBe managed.getId
+      converted.getChanged.getUsers should contain theSameElementsAs Array(primaryUser, secondaryUser)
+      val decrypted = Try(
+        secondarySdk.documentDecryptUnmanaged(converted.getEncryptedData, converted.getEncryptedDeks)
+      ).toEither.value
+      decrypted.getDecryptedData shouldBe bytes
+    }
+    "fail converting to a managed id that already exists" in {
+      val opts = new DocumentEncryptOpts(null, null, true, Array(), Array(), null)
+      val unmanaged = Try(primarySdk.documentEncryptUnmanaged(Array(1.toByte), opts)).toEither.value
+      Try(primarySdk.documentUnmanagedToManaged(unmanaged.getEncryptedData, unmanaged.getEncryptedDeks)).toEither.value
+      val result =
+        Try(primarySdk.documentUnmanagedToManaged(unmanaged.getEncryptedData, unmanaged.getEncryptedDeks)).toEither
+      result.isLeft shouldBe true
+    }
+  }
+
+  "Document Direct ByteBuffer" should {
+    "roundtrip managed documents" in {
+      val bytes = Array(1, 2, 3, 4).map(_.toByte)
+      val data = java.nio.ByteBuffer.allocateDirect(bytes.length).put(bytes)
+      data.flip()
+      val encrypted = java.nio.ByteBuffer.allocateDirect(primarySdk.documentEncryptedSize(bytes.length).toInt)
+      val encryptResult = Try(primarySdk.documentEncrypt(data, encrypted, new DocumentEncryptOpts)).toEither.value
+      data.remaining shouldBe 0
+      encrypted.position shouldBe encryptResult.getLength
+      encrypted.flip()
+
+      val decrypted = java.nio.ByteBuffer.allocateDirect(IronOxide.documentDecryptedSize(encrypted).toInt)
+      val decryptResult = Try(primarySdk.documentDecrypt(encrypted, decrypted)).toEither.value
+      decryptResult.getId shouldBe encryptResult.getId
+      decrypted.flip()
+      val out = new Array[Byte](decrypted.remaining)
+      decrypted.get(out)
+      out shouldBe bytes
+    }
+    "roundtrip unmanaged documents" in {
+      val bytes = Array(5, 6, 7).map(_.toByte)
+      val data = java.nio.ByteBuffer.allocateDirect(bytes.length).put(bytes)
+      data.flip()
+      val encrypted = java.nio.ByteBuffer.allocateDirect(primarySdk.documentEncryptedSize(bytes.length).toInt)
+      val encryptResult =
+        Try(primarySdk.documentEncryptUnmanaged(data, encrypted, new DocumentEncryptOpts)).toEither.value
+      encrypted.flip()
+
+      val decrypted = java.nio.ByteBuffer.allocateDirect(bytes.length)
+      val edeks = encryptResult.getEncryptedDeks.get.getBytes
+      Try(primarySdk.documentDecryptUnmanaged(encrypted, edeks, decrypted)).toEither.value.getLength shouldBe bytes.length
+    }
+    "fail when the output buffer is too small" in {
+      val data = java.nio.ByteBuffer.allocateDirect(10)
+      val result = Try(primarySdk.documentEncrypt(data, java.nio.ByteBuffer.allocateDirect(10), new DocumentEncryptOpts))
+      result.toEither.leftValue.getMessage should include("too small")
+    }
+    "reject heap buffers" in {
+      val result = Try(primarySdk.documentDecrypt(java.nio.ByteBuffer.allocate(10), java.nio.ByteBuffer.allocateDirect(10)))
+      result.toEither.leftValue shouldBe an[IllegalArgumentException]
+    }
+    "reject read-only output buffers" in {
+      val data = java.nio.ByteBuffer.allocateDirect(10)
+      val encrypted = java.nio.ByteBuffer.allocateDirect(primarySdk.documentEncryptedSize(10).toInt).asReadOnlyBuffer
+      val result = Try(primarySdk.documentEncrypt(data, encrypted, new DocumentEncryptOpts))
+      result.toEither.leftValue shouldBe a[java.nio.ReadOnlyBufferException]
+    }
+    "size output buffers large enough for any document ID" in {
+      def randomId(length: Int) = DocumentId.validate((java.util.UUID.randomUUID.toString * 3).take(length))
+      for {
+        length <- List(0, 1, 100, 10000)
+        id <- List(null, randomId(36), randomId(100))
+      } {
+        val bytes = Array.fill(length)(1.toByte)
+        val opts = new DocumentEncryptOpts(id, null, true, Array(), Array(), null)
+        val managed = Try(primarySdk.documentEncrypt(bytes, opts)).toEither.value
+        val unmanaged = Try(primarySdk.documentEncryptUnmanaged(bytes, opts)).toEither.value
+        primarySdk.documentEncryptedSize(length) should be >= managed.getEncryptedData.length.toLong
+        primarySdk.documentEncryptedSize(length) should be >= unmanaged.getEncryptedData.length.toLong
+      }
+    }
+  }
+
   "Document Get Id From Bytes" should {
     "return the document's ID" in {
       val encryptResult = Try(primarySdk.documentEncrypt(Array(), new DocumentEncryptOpts)).toEither.value
@@ -182,6 +436,47 @@ class DocumentTests extends TestSuite {
     }
   }
 
+  "Document Header Parse" should {
+    "read the header without an SDK" in {
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), new DocumentEncryptOpts)).toEither.value
+      val header = Try(DocumentHeader.parse(encryptResult.getEncryptedData)).toEither.value
+      header.getVersion shouldBe 2
+      header.getId shouldBe encryptResult.getId
+      header.getSegmentId should be > 0L
+      header.getLength should be < encryptResult.getEncryptedData.length.toLong
+    }
+    "fail on truncated data" in {
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), new DocumentEncryptOpts)).toEither.value
+      val result = Try(DocumentHeader.parse(encryptResult.getEncryptedData.take(10))).toEither
+      result.leftValue.getMessage should include("truncated")
+    }
+    "fail on data that isn't an encrypted document" in {
+      val result = Try(DocumentHeader.parse(Array(1, 2, 3).map(_.toByte))).toEither
+      result.leftValue.getMessage should include("may not be an IronCore encrypted document")
+    }
+  }
+
+  "Document EDEK Parse" should {
+    "list recipients without an SDK" in {
+      val groupId = Try(primarySdk.groupCreate(new GroupCreateOpts)).toEither.value.getId
+      val opts = new DocumentEncryptOpts(null, null, true, Array(secondaryUser), Array(groupId), null)
+      val encryptResult = Try(primarySdk.documentEncryptUnmanaged(Array(1.toByte), opts)).toEither.value
+      val info = Try(EdekInfo.parse(encryptResult.getEncryptedDeks)).toEither.value
+
+      info.getId shouldBe encryptResult.getId
+      info.getUsers should contain theSameElementsAs Array(primaryUser, secondaryUser)
+      info.getGroups shouldBe Array(groupId)
+      val secondaryKey = Try(primarySdk.userGetPublicKey(Array(secondaryUser))).toEither.value.head.getPublicKey
+      val recipient = info.getRecipients.find(_.getUserOrGroup.getId == secondaryUser.getId).value
+      recipient.getPublicKey.get shouldBe secondaryKey
+      recipient.getFingerprint.get shouldBe secondaryKey.getFingerprint
+    }
+    "fail on bytes that aren't EDEKs" in {
+      val result = Try(EdekInfo.parse(Array(0xff, 0xff).map(_.toByte))).toEither
+      result.leftValue.getMessage should include("aren't IronCore EDEKs")
+    }
+  }
+
   "Document Get Metadata" should {
     "Return an error when retrieving a document that doesn't exist" in {
       val docID = DocumentId.validate("not-a-document-ID-that-exists=/")
@@ -221,6 +516,48 @@ class DocumentTests extends TestSuite {
     }
   }
 
+  "Document Explain Access" should {
+    "expand groups and mark users with both kinds of access" in {
+      val groupCreate = Try(
+        primarySdk.groupCreate(new GroupCreateOpts(null, null, true, true, null, Array(), Array(secondaryUser), false))
+      ).toEither.value
+      val opts = new DocumentEncryptOpts(null, null, true, Array(), Array(groupCreate.getId), null)
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), opts)).toEither.value
+      val report = Try(primarySdk.documentExplainAccess(encryptResult.getId)).toEither.value
+      report.getDocumentId shouldBe encryptResult.getId
+      report.isComplete shouldBe true
+      report.getGroups.map(_.getId) shouldBe Array(groupCreate.getId)
+      val users = report.getUsers.map(u => u.getId -> u).toMap
+      users(primaryUser).hasDirectAndGroupAccess shouldBe true
+      users(secondaryUser).hasDirectAccess shouldBe false
+      users(secondaryUser).getViaGroups shouldBe Array(groupCreate.getId)
+      report.toJson should include(s""""documentId":"${encryptResult.getId.getId}"""")
+    }
+    "mark groups whose members can't be seen" in {
+      val groupCreate = Try(secondarySdk.groupCreate(new GroupCreateOpts)).toEither.value
+      val opts = new DocumentEncryptOpts(null, null, true, Array(), Array(groupCreate.getId), null)
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), opts)).toEither.value
+      val report = Try(primarySdk.documentExplainAccess(encryptResult.getId)).toEither.value
+      report.isComplete shouldBe false
+      report.getGroups.head.isMembersVisible shouldBe false
+      report.getUsers.map(_.getId) shouldBe Array(primaryUser)
+    }
+    "still report when a group can't be read" in {
+      val groupCreate = Try(secondarySdk.groupCreate(new GroupCreateOpts)).toEither.value
+      val opts = new DocumentEncryptOpts(null, null, true, Array(), Array(groupCreate.getId), null)
+      val encryptResult = Try(primarySdk.documentEncrypt(Array(1.toByte), opts)).toEither.value
+      // once the group is deleted, looking it up fails
+      Try(secondarySdk.groupDelete(groupCreate.getId)).toEither.value
+      Try(primarySdk.groupGetMetadata(groupCreate.getId)).isFailure shouldBe true
+      val report = Try(primarySdk.documentExplainAccess(encryptResult.getId)).toEither.value
+      report.isComplete shouldBe false
+      val group = report.getGroups.find(_.getId == groupCreate.getId).value
+      group.isMembersVisible shouldBe false
+      group.getError.isPresent shouldBe true
+      report.getUsers.map(_.getId) shouldBe Array(primaryUser)
+    }
+  }
+
   "Document Get Metadata Unmanaged" should {
     "return metadata from EDEKs" in {
       val bytes = Array(1, 2, 3).map(_.toByte)
@@ -250,6 +587,14 @@ class DocumentTests extends TestSuite {
       val result = Try(primarySdk.documentGetMetadataUnmanaged(garbage))
       result.isFailure shouldBe true
     }
+    "succeed with a per-call timeout" in {
+      val encryptResult =
+        Try(primarySdk.documentEncryptUnmanaged(Array(1.toByte), new DocumentEncryptOpts)).toEither.value
+      val metadata = Try(
+        primarySdk.documentGetMetadataUnmanaged(encryptResult.getEncryptedDeks, Duration.fromSecs(30))
+      ).toEither.value
+      metadata.getId shouldBe encryptResult.getId
+    }
   }
 
   "Document Get Id From Bytes Unmanaged" should {
@@ -410,6 +755,29 @@ class DocumentTests extends TestSuite {
         ()
       }
     }
+    "not leave output behind when timing out" in {
+      val dir = java.nio.file.Files.createTempDirectory("ironoxide-test-timeout")
+      val sourcePath = dir.resolve("source.txt")
+      val encryptedPath = dir.resolve("encrypted.iron")
+      try {
+        java.nio.file.Files.write(sourcePath, "Hello, streaming encryption!".getBytes("UTF-8"))
+        val resp = Try(
+          primarySdk.documentFileEncryptUnmanaged(
+            sourcePath.toString,
+            encryptedPath.toString,
+            new DocumentEncryptOpts,
+            Duration.fromMillis(1)
+          )
+        ).toEither
+        resp.leftValue.getMessage should include("timed out")
+        // give the abandoned operation time to finish and clean up after itself
+        Thread.sleep(5000)
+        java.nio.file.Files.list(dir).iterator.asScala.toList shouldBe List(sourcePath)
+      } finally {
+        java.nio.file.Files.list(dir).iterator.asScala.foreach(java.nio.file.Files.delete)
+        java.nio.file.Files.delete(dir)
+      }
+    }
   }
 
 }
@@ -138,6 +138,53 @@ class GroupTests extends TestSuite {
     }
   }
 
+  "Group List Filtered" should {
+    "only return groups with the given name" in {
+      val groupName = GroupName.validate(java.util.UUID.randomUUID.toString)
+      val groupCreate = Try(
+        primarySdk.groupCreate(new GroupCreateOpts(null, groupName, true, true, null, Array(), Array(), false))
+      ).toEither.value
+      val filter = new GroupListFilter(false, false, false, groupName)
+      val listResult = Try(primarySdk.groupListFiltered(filter)).toEither.value
+      listResult.map(_.getId) shouldBe Array(groupCreate.getId)
+    }
+    "only return groups needing rotation" in {
+      primarySdk.groupCreate(new GroupCreateOpts(null, null, true, true, null, Array(), Array(), true))
+      val filter = new GroupListFilter(true, false, true, null)
+      val listResult = Try(primarySdk.groupListFiltered(filter)).toEither.value
+      listResult.length should be > 0
+      listResult.forall(g => g.isAdmin && g.getNeedsRotation.get.getBoolean) shouldBe true
+    }
+  }
+
+  "Group Resolve Name" should {
+    "find a uniquely named group" in {
+      val groupName = GroupName.validate(java.util.UUID.randomUUID.toString)
+      val groupCreate = Try(
+        primarySdk.groupCreate(new GroupCreateOpts(null, groupName, true, true, null, Array(), Array(), false))
+      ).toEither.value
+      val resolution = Try(primarySdk.groupResolveName(groupName)).toEither.value
+      resolution.getId.get shouldBe groupCreate.getId
+      resolution.isAmbiguous shouldBe false
+    }
+    "report ambiguous names" in {
+      val groupName = GroupName.validate(java.util.UUID.randomUUID.toString)
+      val opts = new GroupCreateOpts(null, groupName, true, true, null, Array(), Array(), false)
+      val first = Try(primarySdk.groupCreate(opts)).toEither.value
+      val second = Try(primarySdk.groupCreate(opts)).toEither.value
+      val resolution = Try(primarySdk.groupResolveName(groupName)).toEither.value
+      resolution.isAmbiguous shouldBe true
+      resolution.getId.isPresent shouldBe false
+      resolution.getMatches.toSet shouldBe Set(first.getId, second.getId)
+    }
+    "find nothing for an unused name" in {
+      val groupName = GroupName.validate(java.util.UUID.randomUUID.toString)
+      val resolution = Try(primarySdk.groupResolveName(groupName)).toEither.value
+      resolution.getMatches shouldBe empty
+      resolution.getId.isPresent shouldBe false
+    }
+  }
+
   "Add and Remove Member" should {
     "add and remove a member from a group" in {
       val groupCreate = Try(primarySdk.groupCreate(new GroupCreateOpts)).toEither.value
@@ -172,6 +219,43 @@ class GroupTests extends TestSuite {
     }
   }
 
+  "Group Sync Membership" should {
+    "only plan changes on a dry run" in {
+      val groupCreate = Try(primarySdk.groupCreate(new GroupCreateOpts)).toEither.value
+      val syncResult = Try(
+        primarySdk.groupSyncMembership(groupCreate.getId, Array(primaryUser), Array(secondaryUser), true)
+      ).toEither.value
+      syncResult.isDryRun shouldBe true
+      syncResult.getChanges.map(c => (c.getUser, c.getChange)).toSet shouldBe Set(
+        (secondaryUser, GroupSyncChange.AddMember),
+        (primaryUser, GroupSyncChange.RemoveMember)
+      )
+      syncResult.getSucceeded shouldBe empty
+      val group = Try(primarySdk.groupGetMetadata(groupCreate.getId)).toEither.value
+      group.getMemberList.get.getList shouldBe Array(primaryUser)
+    }
+    "apply only the needed changes" in {
+      val groupCreate = Try(primarySdk.groupCreate(new GroupCreateOpts)).toEither.value
+      val syncResult = Try(
+        primarySdk.groupSyncMembership(groupCreate.getId, Array(primaryUser, secondaryUser), Array(primaryUser), false)
+      ).toEither.value
+      syncResult.getChanges should have length 1
+      syncResult.getSucceeded.head.getUser shouldBe secondaryUser
+      syncResult.getSucceeded.head.getChange shouldBe GroupSyncChange.AddAdmin
+      syncResult.getFailed shouldBe empty
+      val secondaryGet = Try(secondarySdk.groupGetMetadata(groupCreate.getId)).toEither.value
+      secondaryGet.isAdmin shouldBe true
+      secondaryGet.isMember shouldBe false
+    }
+    "fail for non-admin" in {
+      val groupCreate = Try(
+        primarySdk.groupCreate(new GroupCreateOpts(null, null, true, true, null, Array(), Array(secondaryUser), false))
+      ).toEither.value
+      val syncResult = Try(secondarySdk.groupSyncMembership(groupCreate.getId, Array(), Array(), true))
+      syncResult.isFailure shouldBe true
+    }
+  }
+
   "Group Private Key Rotation" should {
     "rotate a group's private key" in {
       val groupCreate = Try(
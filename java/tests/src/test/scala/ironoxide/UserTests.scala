@@ -127,6 +127,22 @@ class UserTests extends TestSuite {
 
   }
 
+  "User Change Password" should {
+    "change the password protecting the private key" in {
+      val dc = createUserAndDevice()
+      val sdk = IronOxide.initialize(dc, new IronOxideConfig)
+      val newPassword = java.util.UUID.randomUUID.toString
+      val changeResult = Try(sdk.userChangePassword(testUsersPassword, newPassword)).toEither.value
+      changeResult.getUserPublicKey.asBytes should have length 64
+      Try(sdk.userRotatePrivateKey(testUsersPassword)).isFailure shouldBe true
+      Try(sdk.userRotatePrivateKey(newPassword)).toEither.value.getNeedsRotation shouldBe false
+    }
+    "fail for wrong password" in {
+      val changeResult = Try(primarySdk.userChangePassword("wrong password", "new password"))
+      changeResult.isFailure shouldBe true
+    }
+  }
+
   "Generate Device" should {
     "fail with short timeout" in {
       val jwt = generateValidJwt(primaryUser.getId)
@@ -155,6 +171,23 @@ class UserTests extends TestSuite {
     }
   }
 
+  "Initialize and check rotation" should {
+    "report pending rotations and rotate them later" in {
+      val dc = createUserAndDevice()
+      val check = Try(IronOxide.initializeCheckRotation(dc, new IronOxideConfig)).toEither.value
+      check.isRotationNeeded shouldBe true
+      val rotation = check.getRotation.get
+      rotation.getUserRotationNeeded.get shouldBe dc.getAccountId
+      rotation.getGroupRotationNeeded shouldBe empty
+      val rotateResult = Try(check.getIronOxide.rotateAll(rotation, testUsersPassword, null)).toEither.value
+      rotateResult.getUser.get.getNeedsRotation shouldBe false
+      rotateResult.getGroups shouldBe empty
+      val check2 = Try(IronOxide.initializeCheckRotation(dc, new IronOxideConfig)).toEither.value
+      check2.isRotationNeeded shouldBe false
+      check2.getRotation.isPresent shouldBe false
+    }
+  }
+
   "Export Public Key Cache" should {
     "export non-empty cache after operations" in {
       // ensure the cache is populated by doing an operation
@@ -248,4 +281,71 @@ class UserTests extends TestSuite {
       groupList.isFailure shouldBe true
     }
   }
+
+  "Delete Devices" should {
+    "delete every device except the current one" in {
+      val jwt = generateValidJwt()
+      val _ = IronOxide.userCreate(jwt, testUsersPassword, new UserCreateOpts, null)
+      val devices = (1 to 3).map(_ =>
+        Try(IronOxide.generateNewDevice(jwt, testUsersPassword, new DeviceCreateOpts, null)).toEither.value
+      )
+      val sdk = Try(IronOxide.initialize(new DeviceContext(devices.head), new IronOxideConfig)).toEither.value
+      val report = Try(sdk.userDeleteOtherDevices).toEither.value
+      report.getFailed shouldBe empty
+      report.getDeleted.toSet shouldBe devices.tail.map(_.getDeviceId).toSet
+      val remaining = Try(sdk.userListDevices).toEither.value.getResult
+      remaining.map(_.getId) shouldBe Array(devices.head.getDeviceId)
+    }
+    "only delete devices not updated since the given time" in {
+      val jwt = generateValidJwt()
+      val _ = IronOxide.userCreate(jwt, testUsersPassword, new UserCreateOpts, null)
+      val dar = Try(IronOxide.generateNewDevice(jwt, testUsersPassword, new DeviceCreateOpts, null)).toEither.value
+      val other = Try(IronOxide.generateNewDevice(jwt, testUsersPassword, new DeviceCreateOpts, null)).toEither.value
+      val sdk = Try(IronOxide.initialize(new DeviceContext(dar), new IronOxideConfig)).toEither.value
+      Try(sdk.userDeleteDevicesNotUpdatedSince(0)).toEither.value.getResult shouldBe empty
+      val report = Try(sdk.userDeleteDevicesNotUpdatedSince(System.currentTimeMillis + 60000)).toEither.value
+      report.getDeleted shouldBe Array(other.getDeviceId)
+    }
+  }
+
+  "Offboard User" should {
+    "remove a user from groups and documents" in {
+      val adminDevice = createUserAndDevice()
+      val adminSdk = Try(IronOxide.initialize(adminDevice, new IronOxideConfig)).toEither.value
+      val leaver = createUserAndDevice().getAccountId
+      val groupCreate = Try(
+        adminSdk.groupCreate(new GroupCreateOpts(null, null, true, true, null, Array(leaver), Array(leaver), false))
+      ).toEither.value
+      val encryptOpts = new DocumentEncryptOpts(null, null, true, Array(leaver), Array(), null)
+      val encryptResult = Try(adminSdk.documentEncrypt(Array(1.toByte), encryptOpts)).toEither.value
+
+      val dryRun = Try(adminSdk.offboardUser(leaver, 4, true)).toEither.value
+      dryRun.isDryRun shouldBe true
+      dryRun.getChanges should have length 3
+      dryRun.getSucceeded shouldBe empty
+      dryRun.getLookupErrors shouldBe empty
+
+      val report = Try(adminSdk.offboardUser(leaver, 4, false)).toEither.value
+      report.getUser shouldBe leaver
+      report.getFailed shouldBe empty
+      report.getSucceeded.map(c => (c.getAction, c.getGroupId.orElse(null), c.getDocumentId.orElse(null))).toSet shouldBe Set(
+        (OffboardAction.RemoveGroupMember, groupCreate.getId, null),
+        (OffboardAction.RemoveGroupAdmin, groupCreate.getId, null),
+        (OffboardAction.RevokeDocumentAccess, null, encryptResult.getId)
+      )
+      val group = Try(adminSdk.groupGetMetadata(groupCreate.getId)).toEither.value
+      group.getMemberList.get.getList should not contain leaver
+      group.getAdminList.get.getList should not contain leaver
+      val document = Try(adminSdk.documentGetMetadata(encryptResult.getId)).toEither.value
+      document.getVisibleToUsers.map(_.getId) should not contain leaver
+    }
+    "fail with a concurrency of zero" in {
+      val result = Try(primarySdk.offboardUser(secondaryUser, 0, true))
+      result.isFailure shouldBe true
+    }
+    "fail with a concurrency above 64" in {
+      val result = Try(primarySdk.offboardUser(secondaryUser, 65, true))
+      result.toEither.leftValue.getMessage should include("at most 64")
+    }
+  }
 }